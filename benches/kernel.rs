@@ -0,0 +1,64 @@
+//! Scalar versus four-lane escape-time kernels on a row-major grid.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mandelbrot_rs::mandelbrot_iterations;
+use mandelbrot_rs::simd::{mandelbrot_iterations_x4, LANES};
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 192;
+const MAX_ITER: u32 = 500;
+const ESCAPE_RADIUS_SQ: f64 = 4.0;
+
+/// A `WIDTH` x `HEIGHT` grid over the rectangle at `corner` with the given size.
+fn grid(corner: (f64, f64), size: (f64, f64)) -> Vec<(f64, f64)> {
+    (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            (
+                corner.0 + x as f64 * size.0 / WIDTH as f64,
+                corner.1 + y as f64 * size.1 / HEIGHT as f64,
+            )
+        })
+        .collect()
+}
+
+fn kernels(c: &mut Criterion) {
+    // The whole set, and the seahorse valley where neighbouring points need
+    // very different iteration counts and lanes idle the most.
+    let views = [
+        ("overview", grid((-2.0, -1.2), (3.0, 2.4))),
+        ("seahorse", grid((-0.8, 0.1), (0.1, 0.075))),
+    ];
+
+    for (name, points) in &views {
+        let mut group = c.benchmark_group(*name);
+
+        group.bench_function("scalar", |b| {
+            b.iter(|| {
+                for &(re, im) in points {
+                    black_box(mandelbrot_iterations(re, im, MAX_ITER, ESCAPE_RADIUS_SQ));
+                }
+            })
+        });
+
+        group.bench_function("x4", |b| {
+            b.iter(|| {
+                for chunk in points.chunks_exact(LANES) {
+                    let real = std::array::from_fn(|lane| chunk[lane].0);
+                    let imag = std::array::from_fn(|lane| chunk[lane].1);
+                    black_box(mandelbrot_iterations_x4(
+                        real,
+                        imag,
+                        MAX_ITER,
+                        ESCAPE_RADIUS_SQ,
+                    ));
+                }
+            })
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, kernels);
+criterion_main!(benches);
@@ -0,0 +1,255 @@
+use num_bigint::BigInt;
+use num_traits::{Pow, Signed, ToPrimitive, Zero};
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Arbitrary-precision binary floating point number, `mantissa * 2^exponent`.
+///
+/// Addition, subtraction and multiplication are exact; callers bound the
+/// growth of the mantissa with [`BigFloat::truncate`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BigFloat {
+    mantissa: BigInt,
+    exponent: i64,
+}
+
+impl BigFloat {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Converts an `f64` exactly. Non-finite values map to zero.
+    pub fn from_f64(value: f64) -> Self {
+        if !value.is_finite() || value == 0.0 {
+            return Self::zero();
+        }
+
+        let bits = value.to_bits();
+        let sign = if bits >> 63 == 0 { 1i64 } else { -1i64 };
+        let raw_exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1u64 << 52) - 1);
+
+        let (mantissa, exponent) = if raw_exponent == 0 {
+            (fraction, -1074) // Subnormal
+        } else {
+            (fraction | (1u64 << 52), raw_exponent - 1075)
+        };
+
+        Self {
+            mantissa: BigInt::from(mantissa) * sign,
+            exponent,
+        }
+    }
+
+    /// Nearest `f64` (truncated to 63 significant bits before rounding).
+    pub fn to_f64(&self) -> f64 {
+        if self.mantissa.is_zero() {
+            return 0.0;
+        }
+
+        let bits = self.mantissa.bits() as i64;
+        let shift = (bits - 63).max(0);
+        let top = (&self.mantissa >> shift as usize).to_i64().unwrap_or(0);
+        ldexp(top as f64, self.exponent + shift)
+    }
+
+    /// Drops low-order mantissa bits so at most `precision` significant bits remain.
+    pub fn truncate(mut self, precision: u32) -> Self {
+        let bits = self.mantissa.bits();
+        if bits > precision as u64 {
+            let shift = bits - precision as u64;
+            self.mantissa >>= shift as usize;
+            self.exponent += shift as i64;
+        }
+        self
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    /// Multiplies by `2^power` exactly.
+    pub fn mul_pow2(mut self, power: i64) -> Self {
+        self.exponent += power;
+        self
+    }
+
+    /// Formats the value in plain decimal notation, rounded to `fraction_digits`
+    /// digits after the decimal point.
+    pub fn to_decimal_string(&self, fraction_digits: usize) -> String {
+        let scaled = self.mantissa.abs() * BigInt::from(10).pow(fraction_digits as u32);
+        let rounded = if self.exponent >= 0 {
+            scaled << self.exponent as usize
+        } else {
+            let shift = (-self.exponent) as usize;
+            (scaled + (BigInt::from(1) << (shift - 1))) >> shift
+        };
+
+        let mut digits = rounded.to_string();
+        if digits.len() <= fraction_digits {
+            digits.insert_str(0, &"0".repeat(fraction_digits + 1 - digits.len()));
+        }
+        if fraction_digits > 0 {
+            digits.insert(digits.len() - fraction_digits, '.');
+        }
+        if self.is_negative() && !rounded.is_zero() {
+            digits.insert(0, '-');
+        }
+        digits
+    }
+
+    fn aligned(a: &BigFloat, b: &BigFloat) -> (BigInt, BigInt, i64) {
+        if a.mantissa.is_zero() {
+            return (BigInt::zero(), b.mantissa.clone(), b.exponent);
+        }
+        if b.mantissa.is_zero() {
+            return (a.mantissa.clone(), BigInt::zero(), a.exponent);
+        }
+
+        let exponent = a.exponent.min(b.exponent);
+        let a_mantissa = &a.mantissa << (a.exponent - exponent) as usize;
+        let b_mantissa = &b.mantissa << (b.exponent - exponent) as usize;
+        (a_mantissa, b_mantissa, exponent)
+    }
+}
+
+/// Largest power of ten accepted when parsing, far beyond any usable zoom.
+const MAX_DECIMAL_EXPONENT: i64 = 10_000;
+
+/// Error returned when parsing a [`BigFloat`] from a malformed decimal string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBigFloatError;
+
+impl fmt::Display for ParseBigFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid decimal number")
+    }
+}
+
+impl std::error::Error for ParseBigFloatError {}
+
+impl FromStr for BigFloat {
+    type Err = ParseBigFloatError;
+
+    /// Parses decimal notation such as `-0.7436438870371587`, `1.5e-20` or `42`.
+    ///
+    /// Values that are not exact binary fractions keep enough bits to round-trip
+    /// every given digit.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let (number, exponent10) = match s.find(['e', 'E']) {
+            Some(pos) => (
+                &s[..pos],
+                s[pos + 1..]
+                    .parse::<i64>()
+                    .map_err(|_| ParseBigFloatError)?,
+            ),
+            None => (s, 0),
+        };
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty() && fraction.is_empty()
+            || !all_digits(integer)
+            || !all_digits(fraction)
+        {
+            return Err(ParseBigFloatError);
+        }
+
+        let digits: BigInt = format!("{integer}{fraction}")
+            .parse()
+            .map_err(|_| ParseBigFloatError)?;
+        let exponent10 = exponent10
+            .checked_sub(fraction.len() as i64)
+            .filter(|exponent| exponent.unsigned_abs() <= MAX_DECIMAL_EXPONENT as u64)
+            .ok_or(ParseBigFloatError)?;
+
+        let mut value = if exponent10 >= 0 {
+            BigFloat {
+                mantissa: digits * BigInt::from(10).pow(exponent10 as u32),
+                exponent: 0,
+            }
+        } else {
+            let divisor = BigInt::from(10).pow((-exponent10) as u32);
+            let bits = divisor.bits() + digits.bits() + 64;
+            BigFloat {
+                mantissa: (digits << bits as usize) / divisor,
+                exponent: -(bits as i64),
+            }
+        };
+
+        if negative {
+            value = -value;
+        }
+        Ok(value)
+    }
+}
+
+/// `value * 2^exponent` without overflowing the intermediate power of two.
+fn ldexp(mut value: f64, mut exponent: i64) -> f64 {
+    while exponent > 1000 {
+        value *= 2f64.powi(1000);
+        exponent -= 1000;
+    }
+    while exponent < -1000 {
+        value *= 2f64.powi(-1000);
+        exponent += 1000;
+    }
+    value * 2f64.powi(exponent as i32)
+}
+
+impl Add for &BigFloat {
+    type Output = BigFloat;
+
+    fn add(self, rhs: &BigFloat) -> BigFloat {
+        let (a, b, exponent) = BigFloat::aligned(self, rhs);
+        BigFloat {
+            mantissa: a + b,
+            exponent,
+        }
+    }
+}
+
+impl Sub for &BigFloat {
+    type Output = BigFloat;
+
+    fn sub(self, rhs: &BigFloat) -> BigFloat {
+        let (a, b, exponent) = BigFloat::aligned(self, rhs);
+        BigFloat {
+            mantissa: a - b,
+            exponent,
+        }
+    }
+}
+
+impl Mul for &BigFloat {
+    type Output = BigFloat;
+
+    fn mul(self, rhs: &BigFloat) -> BigFloat {
+        if self.mantissa.is_zero() || rhs.mantissa.is_zero() {
+            return BigFloat::zero();
+        }
+
+        BigFloat {
+            mantissa: &self.mantissa * &rhs.mantissa,
+            exponent: self.exponent + rhs.exponent,
+        }
+    }
+}
+
+impl Neg for BigFloat {
+    type Output = BigFloat;
+
+    fn neg(self) -> BigFloat {
+        BigFloat {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Headless renderer: draws a view with the same engine as the explorer and
+//! writes it to a PNG file, no display required.
+
+use mandelbrot_rs::export::write_png;
+use mandelbrot_rs::renderer::MAX_ZOOM;
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: mandelbrot-render [OPTIONS] --output <FILE>
+
+Options:
+  -o, --output <FILE>         PNG file to write
+      --width <PX>            Image width (default: 800)
+      --height <PX>           Image height (default: 600)
+      --center-x <X>          Real part of the view center, or a for lyapunov, any precision
+                              (default: -0.75, or 3.2 for lyapunov)
+      --center-y <Y>          Imaginary part of the view center, or b for lyapunov, any
+                              precision (default: 0.0, or 3.2 for lyapunov)
+      --zoom <PX_PER_UNIT>    Pixels per unit of the plane (default: 200, or 400 for lyapunov)
+      --max-iter <N>          Base iteration count (default: 500)
+      --escape-radius <R>     Escape radius (default: 2.0)
+      --color-offset <T>      Palette offset, 0..1 (default: 0.0)
+      --color-scale <S>       Palette scale (default: 1.0)
+      --coloring <MODE>       smooth or distance (default: smooth)
+      --interior <MODE>       black, period, multiplier or distance (default: black)
+      --precision <MODE>      auto, f64, dd or perturbation (default: auto)
+      --mariani-silver        Fill rectangles with a uniform border without iterating them
+      --supersampling <MODE>  off, 2x2, 3x3, 4x4 or adaptive (default: off)
+      --formula <NAME>        mandelbrot, multibrot, burning-ship, tricorn, celtic, buffalo,
+                              perpendicular, nova, custom, phoenix, magnet-1, magnet-2 or
+                              collatz, each with a -julia variant, julia, newton or
+                              lyapunov (default: mandelbrot)
+      --power <D>             Power of the multibrot formulas, any real number (default: 2)
+      --polynomial <F>        Polynomial of the newton and nova formulas (default: z^3 - 1)
+      --relaxation <A>        Damping of each Newton step (default: 1)
+      --expression <F>        Iteration of the custom formulas, such as 'z^3 + c*sin(z)'
+                              (default: z^2 + c)
+      --start <Z0>            Starting z of the custom formula, in terms of c (default: 0)
+      --parameters <P1,P2..>  Comma-separated values of p1, p2, ... in the custom formulas
+      --sequence <AB..>       Rates of successive steps of the lyapunov formula, A for a and
+                              B for b (default: AB)
+      --phoenix-p-real <X>    Real part of the memory term of the phoenix formulas
+                              (default: -0.5)
+      --phoenix-p-imag <Y>    Imaginary part of the memory term of the phoenix formulas
+                              (default: 0.0)
+      --julia                 Same as --formula julia
+      --julia-c-real <X>      Real part of the Julia constant (default: -0.7)
+      --julia-c-imag <Y>      Imaginary part of the Julia constant (default: 0.27015)
+      --flip-imaginary        Draw the imaginary axis growing upward instead of downward
+      --density <MODE>        off, buddhabrot, nebulabrot or anti-buddhabrot (default: off)
+      --samples <N>           Random points traced for a density plot (default: 4194304)
+      --nebula-limits <R,G,B> Iteration limits of the nebulabrot channels (default: 5000,500,50)
+  -h, --help                  Print this help";
+
+struct Options {
+    params: MandelbrotParams,
+    width: usize,
+    height: usize,
+    output: PathBuf,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for {flag}"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {flag}: {value}"))
+}
+
+/// Like [`parse_value`], for values whose parse errors say what is wrong.
+fn parse_text<T: std::str::FromStr<Err = String>>(
+    flag: &str,
+    value: Option<String>,
+) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for {flag}"))?;
+    value
+        .parse()
+        .map_err(|error| format!("invalid value for {flag}: {error}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut params = MandelbrotParams::default();
+    let mut width = 800;
+    let mut height = 600;
+    let mut output = None;
+    let (mut center_x, mut center_y, mut zoom) = (None, None, None);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => output = Some(parse_value::<PathBuf>(&arg, args.next())?),
+            "--width" => width = parse_value(&arg, args.next())?,
+            "--height" => height = parse_value(&arg, args.next())?,
+            "--center-x" => center_x = Some(parse_value(&arg, args.next())?),
+            "--center-y" => center_y = Some(parse_value(&arg, args.next())?),
+            "--zoom" => zoom = Some(parse_value(&arg, args.next())?),
+            "--max-iter" => params.max_iter = parse_value(&arg, args.next())?,
+            "--escape-radius" => params.escape_radius = parse_value(&arg, args.next())?,
+            "--color-offset" => params.color_offset = parse_value(&arg, args.next())?,
+            "--color-scale" => params.color_scale = parse_value(&arg, args.next())?,
+            "--coloring" => params.coloring = parse_value(&arg, args.next())?,
+            "--interior" => params.interior = parse_value(&arg, args.next())?,
+            "--precision" => params.precision = parse_value(&arg, args.next())?,
+            "--mariani-silver" => params.mariani_silver = true,
+            "--supersampling" => params.supersampling = parse_value(&arg, args.next())?,
+            "--formula" => params.formula = parse_value(&arg, args.next())?,
+            "--sequence" => params.lyapunov_sequence = parse_text(&arg, args.next())?,
+            "--phoenix-p-real" => params.phoenix_p_real = parse_value(&arg, args.next())?,
+            "--phoenix-p-imag" => params.phoenix_p_imag = parse_value(&arg, args.next())?,
+            "--julia" => params.formula = FormulaKind::Julia,
+            "--power" => params.power = parse_value(&arg, args.next())?,
+            "--polynomial" => params.polynomial = parse_text(&arg, args.next())?,
+            "--relaxation" => params.relaxation = parse_value(&arg, args.next())?,
+            "--expression" => params.expression = parse_text(&arg, args.next())?,
+            "--start" => params.start_expression = parse_text(&arg, args.next())?,
+            "--parameters" => {
+                let values: String = parse_value(&arg, args.next())?;
+                params.expression_parameters = values
+                    .split(',')
+                    .map(|value| parse_value(&arg, Some(value.trim().to_string())))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--julia-c-real" => params.julia_c_real = parse_value(&arg, args.next())?,
+            "--julia-c-imag" => params.julia_c_imag = parse_value(&arg, args.next())?,
+            "--flip-imaginary" => params.flip_imaginary = true,
+            "--density" => params.density = parse_value(&arg, args.next())?,
+            "--samples" => params.density_samples = parse_value(&arg, args.next())?,
+            "--nebula-limits" => {
+                let values: String = parse_value(&arg, args.next())?;
+                let limits: Vec<u32> = values
+                    .split(',')
+                    .map(|value| parse_value(&arg, Some(value.trim().to_string())))
+                    .collect::<Result<_, _>>()?;
+                params.nebula_limits = limits
+                    .try_into()
+                    .map_err(|_| format!("{arg} takes three limits: {values}"))?;
+            }
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+
+    // The default view depends on the formula, which may come after the view flags.
+    params.reset_view();
+    params.center_x = center_x.unwrap_or(params.center_x);
+    params.center_y = center_y.unwrap_or(params.center_y);
+    params.zoom = zoom.unwrap_or(params.zoom);
+
+    if width == 0 || height == 0 {
+        return Err("width and height must be positive".to_string());
+    }
+    if !params.zoom.is_finite() || params.zoom <= 0.0 {
+        return Err("zoom must be positive and finite".to_string());
+    }
+    params.zoom = params.zoom.min(MAX_ZOOM);
+    let output = output.ok_or("missing required --output <FILE>")?;
+
+    Ok(Some(Options {
+        params,
+        width,
+        height,
+        output,
+    }))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let start = Instant::now();
+    let frame = Renderer::new(options.width, options.height).render(&options.params);
+    let elapsed = start.elapsed();
+
+    if let Err(err) = write_png(&frame, &options.params, &options.output) {
+        eprintln!("error: failed to write {}: {err}", options.output.display());
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "Rendered {}x{} in {:.1}ms -> {}",
+        frame.width,
+        frame.height,
+        elapsed.as_secs_f64() * 1000.0,
+        options.output.display()
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,70 @@
+//! The Collatz map extended to the complex plane,
+//! `(2 + 7z - (2 + 5z) cos(pi z)) / 4`, which halves even integers and sends
+//! odd ones to `3z + 1`. A constant `c` is added after every step.
+//!
+//! Off the real axis the cosine grows exponentially with the imaginary part,
+//! so orbits escape by it rather than by their modulus, and far too fast for
+//! escape-time smoothing to mean anything.
+
+use std::f64::consts::PI;
+
+use crate::complex::{add, cos, mul, norm_sq, scale, sub, Complex};
+use crate::formula::Formula;
+
+/// One Collatz step from `z`, plus `c`.
+fn collatz_step(z: Complex, c: Complex) -> Complex {
+    let even = (2.0 + 7.0 * z.0, 7.0 * z.1);
+    let odd = mul((2.0 + 5.0 * z.0, 5.0 * z.1), cos(scale(z, PI)));
+    add(scale(sub(even, odd), 0.25), c)
+}
+
+/// Whether the imaginary part of `z` is past the escape radius. Orbits that
+/// overflowed count as escaped too.
+fn escaped(z: Complex, escape_radius_sq: f64) -> bool {
+    z.1 * z.1 > escape_radius_sq || !norm_sq(z).is_finite()
+}
+
+/// Collatz orbits from 0 with the point as `c`.
+pub struct Collatz;
+
+impl Formula for Collatz {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        collatz_step(z, c)
+    }
+
+    fn escaped(&self, z: Complex, escape_radius_sq: f64) -> bool {
+        escaped(z, escape_radius_sq)
+    }
+
+    fn smooth_iterations(&self, iter: u32, _: Complex) -> f64 {
+        iter as f64
+    }
+}
+
+/// Collatz orbits from the point, shifted by `c` after every step; with `c`
+/// at 0 this is the plain Collatz fractal.
+pub struct CollatzJulia {
+    pub c: Complex,
+}
+
+impl Formula for CollatzJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        collatz_step(z, c)
+    }
+
+    fn escaped(&self, z: Complex, escape_radius_sq: f64) -> bool {
+        escaped(z, escape_radius_sq)
+    }
+
+    fn smooth_iterations(&self, iter: u32, _: Complex) -> f64 {
+        iter as f64
+    }
+}
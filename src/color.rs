@@ -0,0 +1,225 @@
+use std::f64::consts::TAU;
+use std::sync::OnceLock;
+
+use crate::params::InteriorColoring;
+
+pub fn colorize_pixel(iterations: f64, max_iter: u32, color_scale: f64, color_offset: f64) -> u32 {
+    if iterations >= max_iter as f64 {
+        return 0x000000; // Black for points in the set
+    }
+
+    palette(((iterations / max_iter as f64) * color_scale + color_offset).fract())
+}
+
+/// The gradient behind the colorings, for `t` in `0..1`.
+fn palette(t: f64) -> u32 {
+    // Enhanced color palette
+    let r = (9.0 * (1.0 - t) * t * t * t * 255.0) as u8;
+    let g = (15.0 * (1.0 - t) * (1.0 - t) * t * t * 255.0) as u8;
+    let b = (8.5 * (1.0 - t) * (1.0 - t) * (1.0 - t) * t * 255.0) as u8;
+
+    ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
+}
+
+/// Distance to the set, in pixels, over which distance-estimate coloring fades to black.
+const DISTANCE_FALLOFF: f64 = 4.0;
+
+/// Colors a point by its estimated distance to the set, in pixels: a light
+/// tint of the palette far away, black on the boundary and inside.
+pub fn colorize_distance(
+    iterations: f64,
+    distance: f64,
+    max_iter: u32,
+    color_scale: f64,
+    color_offset: f64,
+) -> u32 {
+    if iterations >= max_iter as f64 {
+        return 0x000000;
+    }
+
+    let tint = colorize_pixel(iterations, max_iter, color_scale, color_offset);
+    let shade = (distance / DISTANCE_FALLOFF).clamp(0.0, 1.0).sqrt();
+    (0..3).fold(0, |packed, channel| {
+        let value = (tint >> (16 - 8 * channel)) & 0xFF;
+        (packed << 8) | (shade * (3 * 255 + value) as f64 / 4.0) as u32
+    })
+}
+
+/// Hue steps between consecutive periods; the golden ratio keeps nearby periods far apart.
+const PERIOD_HUE_STEP: f64 = 0.618_033_988_749_895;
+
+/// Colors a point inside the set from its attracting cycle, with `distance`
+/// (the interior estimate) in pixels. Points whose cycle was not located stay black.
+pub fn colorize_interior(
+    mode: InteriorColoring,
+    period: u32,
+    multiplier: (f64, f64),
+    distance: f64,
+    color_scale: f64,
+    color_offset: f64,
+) -> u32 {
+    match mode {
+        InteriorColoring::Black => 0x000000,
+        InteriorColoring::Period if period == 0 => 0x000000,
+        InteriorColoring::Period => {
+            let hue = (period as f64 * PERIOD_HUE_STEP * color_scale + color_offset).fract();
+            hsv(hue, 0.6, 0.9)
+        }
+        InteriorColoring::Multiplier if multiplier == (0.0, 0.0) => 0x000000,
+        InteriorColoring::Multiplier => {
+            let (re, im) = multiplier;
+            let angle = im.atan2(re) / TAU + 0.5;
+            let magnitude = (re * re + im * im).sqrt();
+            hsv(
+                (angle * color_scale + color_offset).fract(),
+                0.7,
+                1.0 - magnitude,
+            )
+        }
+        InteriorColoring::Distance if distance <= 0.0 => 0x000000,
+        InteriorColoring::Distance => {
+            // Bands get thinner towards the boundary, where they fade out.
+            let t = (distance.ln() * color_scale / 4.0 + color_offset).rem_euclid(1.0);
+            let shade = (distance / DISTANCE_FALLOFF).clamp(0.0, 1.0).sqrt();
+            scale_color(palette(t), shade)
+        }
+    }
+}
+
+/// Convergence steps over which the color of a root fades by a factor of `e`.
+const ROOT_FADE_STEPS: f64 = 16.0;
+
+/// Colors a point by the root its Newton orbit converged to, darker the more
+/// steps that took.
+pub fn colorize_root(root: u32, iterations: f64, color_scale: f64, color_offset: f64) -> u32 {
+    let hue = (root as f64 * PERIOD_HUE_STEP * color_scale + color_offset).fract();
+    hsv(hue, 0.7, (-iterations / ROOT_FADE_STEPS).exp())
+}
+
+/// Colors a pixel of a density plot from its hit count per channel, relative
+/// to the largest count of each channel. The square root keeps the faint
+/// outer orbits visible; `color_scale` brightens or darkens the whole plot.
+pub fn colorize_density(counts: [u32; 3], peaks: [u32; 3], color_scale: f64) -> u32 {
+    (0..3).fold(0, |packed, channel| {
+        let share = counts[channel] as f64 / peaks[channel].max(1) as f64;
+        let value = (share.sqrt() * color_scale * 255.0)
+            .round()
+            .clamp(0.0, 255.0);
+        (packed << 8) | value as u32
+    })
+}
+
+/// Gradient for negative Lyapunov exponents, where orbits settle: black at
+/// zero through amber to pale gold.
+const LYAPUNOV_STABLE: [u32; 4] = [0x000000, 0x6B3A00, 0xE8A317, 0xFFF3C4];
+
+/// Gradient for positive Lyapunov exponents, where orbits are chaotic: black
+/// at zero through deep blue to ice.
+const LYAPUNOV_CHAOTIC: [u32; 4] = [0x000000, 0x0B1F66, 0x2F6FD0, 0xC8E8FF];
+
+/// Colors a point of a Lyapunov fractal by its exponent, with separate
+/// gradients for either sign; both start black at zero, where the orbit is
+/// on the edge of chaos. `color_scale` sets how fast they brighten.
+pub fn colorize_lyapunov(exponent: f64, color_scale: f64) -> u32 {
+    let t = 1.0 - (-exponent.abs() * color_scale).exp();
+    if exponent < 0.0 {
+        gradient(&LYAPUNOV_STABLE, t)
+    } else {
+        gradient(&LYAPUNOV_CHAOTIC, t)
+    }
+}
+
+/// Interpolates between evenly spaced packed colors, for `t` in `0..=1`.
+fn gradient(stops: &[u32], t: f64) -> u32 {
+    let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let index = (position as usize).min(stops.len() - 2);
+    let fraction = position - index as f64;
+    let (from, to) = (stops[index], stops[index + 1]);
+    (0..3).fold(0, |packed, channel| {
+        let shift = 16 - 8 * channel;
+        let a = ((from >> shift) & 0xFF) as f64;
+        let b = ((to >> shift) & 0xFF) as f64;
+        (packed << 8) | (a + (b - a) * fraction).round() as u32
+    })
+}
+
+/// Packs a color given as hue, saturation and value, all in `0..=1`.
+fn hsv(hue: f64, saturation: f64, value: f64) -> u32 {
+    let channel = |n: f64| {
+        let k = (n + hue * 6.0) % 6.0;
+        let c = value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0);
+        (c * 255.0).round().clamp(0.0, 255.0) as u32
+    };
+    (channel(5.0) << 16) | (channel(3.0) << 8) | channel(1.0)
+}
+
+/// Multiplies every channel of a packed color by `factor` in `0..=1`.
+fn scale_color(color: u32, factor: f64) -> u32 {
+    (0..3).fold(0, |packed, channel| {
+        let value = (color >> (16 - 8 * channel)) & 0xFF;
+        (packed << 8) | (value as f64 * factor) as u32
+    })
+}
+
+/// Averages packed `0xRRGGBB` colors in linear light, as a display would mix
+/// them, rather than averaging the gamma-encoded sRGB values.
+pub fn average_colors(colors: &[u32]) -> u32 {
+    let table = srgb_to_linear_table();
+    let mut sum = [0.0f32; 3];
+    for &color in colors {
+        for (channel, sum) in sum.iter_mut().enumerate() {
+            *sum += table[((color >> (16 - 8 * channel)) & 0xFF) as usize];
+        }
+    }
+
+    sum.iter().fold(0, |packed, &sum| {
+        (packed << 8) | linear_to_srgb(sum / colors.len() as f32) as u32
+    })
+}
+
+/// Largest difference between the channels of two packed `0xRRGGBB` colors.
+pub fn color_difference(a: u32, b: u32) -> u32 {
+    (0..3)
+        .map(|channel| {
+            let shift = 16 - 8 * channel;
+            ((a >> shift) & 0xFF).abs_diff((b >> shift) & 0xFF)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Linear intensity of each 8-bit sRGB channel value.
+fn srgb_to_linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|value| {
+            let c = value as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    })
+}
+
+fn linear_to_srgb(linear: f32) -> u8 {
+    let c = if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Expands packed `0xRRGGBB` pixels into an opaque RGBA byte buffer.
+pub fn pixels_to_rgba(pixels: &[u32]) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(pixels.len() * 4);
+    for &pixel in pixels {
+        rgba.push(((pixel >> 16) & 0xFF) as u8); // R
+        rgba.push(((pixel >> 8) & 0xFF) as u8); // G
+        rgba.push((pixel & 0xFF) as u8); // B
+        rgba.push(255); // A
+    }
+    rgba
+}
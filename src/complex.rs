@@ -0,0 +1,105 @@
+//! Complex arithmetic on plain `(real, imaginary)` pairs, which keeps
+//! formulas free of wrapper types and lets the compiler keep both parts in registers.
+
+/// A complex number as `(real, imaginary)`.
+pub type Complex = (f64, f64);
+
+/// Whole powers up to this size are computed by repeated squaring.
+const MAX_WHOLE_POWER: f64 = 64.0;
+
+pub fn add(a: Complex, b: Complex) -> Complex {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+pub fn sub(a: Complex, b: Complex) -> Complex {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+pub fn mul(a: Complex, b: Complex) -> Complex {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+pub fn div(a: Complex, b: Complex) -> Complex {
+    let d = norm_sq(b);
+    ((a.0 * b.0 + a.1 * b.1) / d, (a.1 * b.0 - a.0 * b.1) / d)
+}
+
+pub fn scale(a: Complex, factor: f64) -> Complex {
+    (a.0 * factor, a.1 * factor)
+}
+
+pub fn norm_sq(a: Complex) -> f64 {
+    a.0 * a.0 + a.1 * a.1
+}
+
+/// Principal square root.
+pub fn sqrt(a: Complex) -> Complex {
+    let r = norm_sq(a).sqrt();
+    let re = ((r + a.0) / 2.0).sqrt();
+    let im = ((r - a.0) / 2.0).sqrt();
+    (re, if a.1 < 0.0 { -im } else { im })
+}
+
+/// `z^d`: exact repeated squaring for whole `d`, the principal branch otherwise.
+/// Negative powers of 0 are infinite.
+pub fn pow(z: Complex, d: f64) -> Complex {
+    if d.fract() == 0.0 && d.abs() <= MAX_WHOLE_POWER {
+        let mut n = d.abs() as u32;
+        let (mut base, mut result) = (z, (1.0, 0.0));
+        while n > 0 {
+            if n & 1 == 1 {
+                result = mul(result, base);
+            }
+            base = mul(base, base);
+            n >>= 1;
+        }
+        return if d < 0.0 {
+            div((1.0, 0.0), result)
+        } else {
+            result
+        };
+    }
+
+    let r_sq = norm_sq(z);
+    if r_sq == 0.0 {
+        return if d > 0.0 {
+            (0.0, 0.0)
+        } else {
+            (f64::INFINITY, 0.0)
+        };
+    }
+    let modulus = r_sq.powf(d / 2.0);
+    let (sin, cos) = (z.1.atan2(z.0) * d).sin_cos();
+    (modulus * cos, modulus * sin)
+}
+
+pub fn exp(z: Complex) -> Complex {
+    let modulus = z.0.exp();
+    let (sin, cos) = z.1.sin_cos();
+    (modulus * cos, modulus * sin)
+}
+
+/// Principal natural logarithm.
+pub fn ln(z: Complex) -> Complex {
+    (0.5 * norm_sq(z).ln(), z.1.atan2(z.0))
+}
+
+pub fn sin(z: Complex) -> Complex {
+    let (sin, cos) = z.0.sin_cos();
+    (sin * z.1.cosh(), cos * z.1.sinh())
+}
+
+pub fn cos(z: Complex) -> Complex {
+    let (sin, cos) = z.0.sin_cos();
+    (cos * z.1.cosh(), -sin * z.1.sinh())
+}
+
+pub fn sinh(z: Complex) -> Complex {
+    let (sin, cos) = z.1.sin_cos();
+    (z.0.sinh() * cos, z.0.cosh() * sin)
+}
+
+pub fn cosh(z: Complex) -> Complex {
+    let (sin, cos) = z.1.sin_cos();
+    (z.0.cosh() * cos, z.0.sinh() * sin)
+}
@@ -0,0 +1,174 @@
+//! Density plots: the Buddhabrot, the Nebulabrot and the anti-Buddhabrot.
+//!
+//! Random points are drawn from the square of half-width `escape_radius`
+//! around the origin and iterated with the selected formula. Every point of
+//! a qualifying orbit adds a hit to the pixel it lands in, so the picture
+//! shows where orbits spend their time. Sampling runs in chunks, each with its
+//! own seeded generator, so a plot comes out the same however the chunks are
+//! spread over threads.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use rayon::prelude::*;
+
+use crate::complex::Complex;
+use crate::formula::{Formula, FormulaKind};
+use crate::fractal::in_main_cardioid_or_bulb;
+use crate::params::{DensityPlot, MandelbrotParams};
+
+/// Random points traced per parallel task.
+pub const CHUNK_SAMPLES: u64 = 1 << 12;
+
+/// SplitMix64: tiny and fast, and enough to spread points evenly.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `-1..1`.
+    fn next_signed(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (2.0 / (1u64 << 53) as f64) - 1.0
+    }
+}
+
+/// Hit counts per pixel and color channel, filled from any number of threads.
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    /// Row-major, three channels per pixel.
+    counts: Vec<AtomicU32>,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            counts: (0..width * height * 3).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    /// Traces the chunks `chunks` of the plot `params` asks for. Returns
+    /// `None` if `cancelled` reported `true` before all of them were done.
+    pub fn trace(
+        &self,
+        params: &MandelbrotParams,
+        chunks: std::ops::Range<u64>,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<()> {
+        let formula = params.build_formula();
+        let tracer = Tracer {
+            formula: formula.as_ref(),
+            radius: params.escape_radius,
+            escape_radius_sq: params.escape_radius * params.escape_radius,
+            limits: params.density_limits(),
+            anti: params.density == DensityPlot::AntiBuddhabrot,
+            // Orbits there never escape, so the Buddhabrot needs no samples from them.
+            skip_bulbs: params.formula == FormulaKind::Mandelbrot
+                && params.density != DensityPlot::AntiBuddhabrot,
+            center: params.center_f64(),
+            zoom: params.zoom,
+            imaginary_direction: params.imaginary_direction(),
+        };
+        chunks.into_par_iter().try_for_each(|chunk| {
+            if cancelled() {
+                return None;
+            }
+            tracer.trace_chunk(self, &mut Rng::new(chunk));
+            Some(())
+        })
+    }
+
+    /// Current counts, as `[red, green, blue]` per pixel.
+    pub fn snapshot(&self) -> Vec<[u32; 3]> {
+        self.counts
+            .chunks(3)
+            .map(|pixel| std::array::from_fn(|channel| pixel[channel].load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+/// Everything needed to trace orbits for one plot.
+struct Tracer<'a> {
+    formula: &'a dyn Formula,
+    /// Half-width of the square points are drawn from.
+    radius: f64,
+    escape_radius_sq: f64,
+    limits: [u32; 3],
+    anti: bool,
+    skip_bulbs: bool,
+    center: Complex,
+    zoom: f64,
+    imaginary_direction: f64,
+}
+
+impl Tracer<'_> {
+    fn trace_chunk(&self, accumulator: &Accumulator, rng: &mut Rng) {
+        let limit = self.limits.iter().copied().max().unwrap_or(0) as usize;
+        let mut orbit: Vec<Complex> = Vec::with_capacity(limit);
+        for _ in 0..CHUNK_SAMPLES {
+            let point = (
+                rng.next_signed() * self.radius,
+                rng.next_signed() * self.radius,
+            );
+            if self.skip_bulbs && in_main_cardioid_or_bulb(point.0, point.1) {
+                continue;
+            }
+
+            let (mut z, c) = self.formula.initial(point);
+            let mut previous = (0.0, 0.0);
+            orbit.clear();
+            let mut escaped = false;
+            let mut converged = false;
+            while orbit.len() < limit {
+                (z, previous) = (self.formula.step_with_previous(z, previous, c), z);
+                if self.formula.escaped(z, self.escape_radius_sq) {
+                    escaped = true;
+                    break;
+                }
+                if self.formula.converged(z) {
+                    converged = true;
+                    break;
+                }
+                orbit.push(z);
+            }
+            // Settled orbits neither escape nor stay bounded in the sense
+            // either plot means, so they are left out of both.
+            if converged {
+                continue;
+            }
+
+            for (channel, &channel_limit) in self.limits.iter().enumerate() {
+                let channel_limit = channel_limit as usize;
+                let escaped_within = escaped && orbit.len() < channel_limit;
+                if escaped_within != self.anti {
+                    for &z in &orbit[..orbit.len().min(channel_limit)] {
+                        self.hit(accumulator, z, channel);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts `z` in `channel` of the pixel showing it, if any.
+    fn hit(&self, accumulator: &Accumulator, z: Complex, channel: usize) {
+        let x = ((z.0 - self.center.0) * self.zoom + accumulator.width as f64 / 2.0).round();
+        let y = ((z.1 - self.center.1) * self.zoom * self.imaginary_direction
+            + accumulator.height as f64 / 2.0)
+            .round();
+        if x >= 0.0 && y >= 0.0 && x < accumulator.width as f64 && y < accumulator.height as f64 {
+            let index = (y as usize * accumulator.width + x as usize) * 3 + channel;
+            accumulator.counts[index].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
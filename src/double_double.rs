@@ -0,0 +1,105 @@
+//! Double-double arithmetic: an unevaluated sum of two `f64`s giving about
+//! 106 bits of mantissa, enough for zooms up to ~1e28 without big integers.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::bigfloat::BigFloat;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+impl DoubleDouble {
+    pub const ZERO: Self = Self { hi: 0.0, lo: 0.0 };
+
+    pub fn from_f64(value: f64) -> Self {
+        Self { hi: value, lo: 0.0 }
+    }
+
+    /// Rounds an arbitrary-precision value to the nearest double-double.
+    pub fn from_bigfloat(value: &BigFloat) -> Self {
+        let hi = value.to_f64();
+        let lo = (value - &BigFloat::from_f64(hi)).to_f64();
+        Self { hi, lo }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    pub fn square(self) -> Self {
+        let (p, e) = two_prod(self.hi, self.hi);
+        quick_two_sum(p, e + 2.0 * self.hi * self.lo)
+    }
+
+    /// Multiplies by two exactly.
+    pub fn double(self) -> Self {
+        Self {
+            hi: 2.0 * self.hi,
+            lo: 2.0 * self.lo,
+        }
+    }
+}
+
+/// `a + b` as a rounded sum plus its exact error, for any `a` and `b`.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Like [`two_sum`], but requires `|a| >= |b|`.
+fn quick_two_sum(a: f64, b: f64) -> DoubleDouble {
+    let s = a + b;
+    DoubleDouble {
+        hi: s,
+        lo: b - (s - a),
+    }
+}
+
+/// `a * b` as a rounded product plus its exact error.
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (s, e) = two_sum(self.hi, rhs.hi);
+        let (t, f) = two_sum(self.lo, rhs.lo);
+        let r = quick_two_sum(s, e + t);
+        quick_two_sum(r.hi, r.lo + f)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        quick_two_sum(p, e + (self.hi * rhs.lo + self.lo * rhs.hi))
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use crate::formula::FormulaKind;
+use crate::params::MandelbrotParams;
+use crate::renderer::Frame;
+
+/// Writes a rendered frame to `path` as an 8-bit RGBA PNG.
+///
+/// The full-precision view location, the formula and the constants it uses
+/// are stored in text chunks so the image can be traced back to its view.
+pub fn write_png(frame: &Frame, params: &MandelbrotParams, path: &Path) -> io::Result<()> {
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        frame.width as u32,
+        frame.height as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let (center_x, center_y) = params.center_to_strings();
+    let formula = params.formula;
+    // For Lyapunov the center is a point `(a, b)` of the rate plane, not of the complex plane.
+    let mut chunks = vec![
+        ("Center X", center_x),
+        ("Center Y", center_y),
+        ("Zoom", format!("{:e}", params.zoom)),
+        ("Formula", formula.label().to_string()),
+        ("Max Iterations", params.max_iter.to_string()),
+    ];
+    if formula.uses_escape_radius() {
+        chunks.push(("Escape Radius", params.escape_radius.to_string()));
+    }
+    if formula.is_julia() {
+        let c = format!("{}, {}", params.julia_c_real, params.julia_c_imag);
+        chunks.push(("Julia C", c));
+    }
+    if formula.uses_power() {
+        chunks.push(("Power", params.power.to_string()));
+    }
+    if formula.is_newton() {
+        chunks.push(("Polynomial", params.polynomial.to_string()));
+        chunks.push(("Relaxation", params.relaxation.to_string()));
+    }
+    if formula.is_custom() {
+        chunks.push(("Expression", params.expression.to_string()));
+        let mut count = params.expression.parameter_count();
+        if formula == FormulaKind::Custom {
+            chunks.push(("Start", params.start_expression.to_string()));
+            count = count.max(params.start_expression.parameter_count());
+        }
+        if count > 0 {
+            // Parameters beyond those set are 0, as in evaluation.
+            let values: Vec<String> = (0..count)
+                .map(|k| params.expression_parameters.get(k).copied().unwrap_or(0.0))
+                .map(|value| value.to_string())
+                .collect();
+            chunks.push(("Parameters", values.join(", ")));
+        }
+    }
+    if formula.is_phoenix() {
+        let p = format!("{}, {}", params.phoenix_p_real, params.phoenix_p_imag);
+        chunks.push(("Phoenix P", p));
+    }
+    if formula.is_lyapunov() {
+        chunks.push(("Sequence", params.lyapunov_sequence.to_string()));
+    }
+    for (keyword, text) in chunks {
+        encoder
+            .add_text_chunk(keyword.to_string(), text)
+            .map_err(io::Error::other)?;
+    }
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(&frame.to_rgba())
+        .map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
@@ -0,0 +1,510 @@
+//! Iteration formulas typed in at runtime, such as `z^3 + c*sin(z)` or
+//! `z*z + c + p1/z`.
+//!
+//! An [`Expression`] is parsed once and compiled to bytecode for a small stack
+//! machine, with constant parts folded, so evaluating it every step needs no
+//! allocation. Expressions see the orbit value `z`, the parameter `c`, the
+//! constants `i`, `pi` and `e`, and user parameters `p1`, `p2`, … set by sliders.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::complex::{
+    add, cos, cosh, div, exp, ln, mul, norm_sq, pow, sin, sinh, sqrt, sub, Complex,
+};
+use crate::formula::Formula;
+use crate::fractal::smooth_iterations;
+
+/// Highest user parameter, `p16`.
+pub const MAX_PARAMETERS: usize = 16;
+
+/// Evaluation stack slots; deeper expressions are rejected when parsed.
+const STACK_SIZE: usize = 32;
+
+/// Parentheses, function calls and exponents nested deeper than this are rejected.
+const MAX_NESTING: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Log,
+    Sqrt,
+    Abs,
+    Conj,
+    Re,
+    Im,
+}
+
+impl Function {
+    const ALL: [(&'static str, Function); 13] = [
+        ("sin", Function::Sin),
+        ("cos", Function::Cos),
+        ("tan", Function::Tan),
+        ("sinh", Function::Sinh),
+        ("cosh", Function::Cosh),
+        ("tanh", Function::Tanh),
+        ("exp", Function::Exp),
+        ("log", Function::Log),
+        ("sqrt", Function::Sqrt),
+        ("abs", Function::Abs),
+        ("conj", Function::Conj),
+        ("re", Function::Re),
+        ("im", Function::Im),
+    ];
+
+    fn named(name: &str) -> Option<Function> {
+        Self::ALL
+            .iter()
+            .find(|(function_name, _)| *function_name == name)
+            .map(|&(_, function)| function)
+    }
+
+    fn apply(self, z: Complex) -> Complex {
+        match self {
+            Function::Sin => sin(z),
+            Function::Cos => cos(z),
+            Function::Tan => div(sin(z), cos(z)),
+            Function::Sinh => sinh(z),
+            Function::Cosh => cosh(z),
+            Function::Tanh => div(sinh(z), cosh(z)),
+            Function::Exp => exp(z),
+            Function::Log => ln(z),
+            Function::Sqrt => sqrt(z),
+            Function::Abs => (norm_sq(z).sqrt(), 0.0),
+            Function::Conj => (z.0, -z.1),
+            Function::Re => (z.0, 0.0),
+            Function::Im => (z.1, 0.0),
+        }
+    }
+}
+
+/// One instruction of the stack machine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Constant(Complex),
+    Z,
+    C,
+    /// User parameter by zero-based index.
+    Parameter(usize),
+    Negate,
+    Call(Function),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl Op {
+    /// Applies a binary operation.
+    fn binary(self, a: Complex, b: Complex) -> Complex {
+        match self {
+            Op::Add => add(a, b),
+            Op::Sub => sub(a, b),
+            Op::Mul => mul(a, b),
+            Op::Div => div(a, b),
+            // Real exponents keep exact repeated squaring for whole powers.
+            Op::Pow if b.1 == 0.0 => pow(a, b.0),
+            Op::Pow => exp(mul(b, ln(a))),
+            _ => unreachable!("{self:?} is not a binary operation"),
+        }
+    }
+
+    /// Applies a unary operation.
+    fn unary(self, a: Complex) -> Complex {
+        match self {
+            Op::Negate => (-a.0, -a.1),
+            Op::Call(function) => function.apply(a),
+            _ => unreachable!("{self:?} is not a unary operation"),
+        }
+    }
+}
+
+/// A compiled formula in `z` and `c`. Expressions compare equal when they
+/// compile to the same program, however they were spaced.
+#[derive(Clone, Debug)]
+pub struct Expression {
+    source: String,
+    program: Vec<Op>,
+    parameter_count: usize,
+}
+
+impl Expression {
+    /// Value at `z` and `c`, with `parameters[k]` as `p(k+1)`; parameters
+    /// beyond the slice are 0.
+    pub fn evaluate(&self, z: Complex, c: Complex, parameters: &[f64]) -> Complex {
+        let mut stack = [(0.0, 0.0); STACK_SIZE];
+        let mut top = 0;
+        for &op in &self.program {
+            match op {
+                Op::Constant(value) => {
+                    stack[top] = value;
+                    top += 1;
+                }
+                Op::Z => {
+                    stack[top] = z;
+                    top += 1;
+                }
+                Op::C => {
+                    stack[top] = c;
+                    top += 1;
+                }
+                Op::Parameter(index) => {
+                    stack[top] = (parameters.get(index).copied().unwrap_or(0.0), 0.0);
+                    top += 1;
+                }
+                Op::Negate | Op::Call(_) => stack[top - 1] = op.unary(stack[top - 1]),
+                Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Pow => {
+                    top -= 1;
+                    stack[top - 1] = op.binary(stack[top - 1], stack[top]);
+                }
+            }
+        }
+        stack[0]
+    }
+
+    /// Number of user parameters the sliders need: the highest `pN` used.
+    pub fn parameter_count(&self) -> usize {
+        self.parameter_count
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.program == other.program
+    }
+}
+
+impl fmt::Display for Expression {
+    /// The expression as it was typed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl std::str::FromStr for Expression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut compiler = Compiler {
+            chars: s.char_indices().peekable(),
+            program: Vec::new(),
+            depth: 0,
+            nesting: 0,
+            parameter_count: 0,
+        };
+        compiler.sum()?;
+        if let Some((column, c)) = compiler.peek() {
+            return Err(format!("unexpected '{c}' at column {}", column + 1));
+        }
+        Ok(Self {
+            source: s.trim().to_string(),
+            program: compiler.program,
+            parameter_count: compiler.parameter_count,
+        })
+    }
+}
+
+/// Recursive-descent parser that emits the program in postfix order as it goes.
+struct Compiler<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    program: Vec<Op>,
+    /// Stack slots the program emitted so far leaves filled.
+    depth: usize,
+    /// Parentheses, calls and exponents currently open.
+    nesting: usize,
+    parameter_count: usize,
+}
+
+impl Compiler<'_> {
+    /// Next non-space character and its index, without consuming it.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.peek().is_some_and(|(_, c)| c == expected) && self.chars.next().is_some()
+    }
+
+    fn push(&mut self, op: Op) -> Result<(), String> {
+        if self.depth == STACK_SIZE {
+            return Err("formula is too deeply nested".to_string());
+        }
+        self.depth += 1;
+        self.program.push(op);
+        Ok(())
+    }
+
+    /// Emits a unary operation, folding it into a constant operand.
+    fn unary(&mut self, op: Op) {
+        match self.program.last_mut() {
+            Some(Op::Constant(value)) => *value = op.unary(*value),
+            _ => self.program.push(op),
+        }
+    }
+
+    /// Emits a binary operation, folding it if both operands are constants.
+    fn binary(&mut self, op: Op) {
+        self.depth -= 1;
+        if let [.., Op::Constant(a), Op::Constant(b)] = self.program[..] {
+            self.program.pop();
+            self.program.pop();
+            self.program.push(Op::Constant(op.binary(a, b)));
+        } else {
+            self.program.push(op);
+        }
+    }
+
+    /// `product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<(), String> {
+        self.product()?;
+        loop {
+            let op = if self.eat('+') {
+                Op::Add
+            } else if self.eat('-') {
+                Op::Sub
+            } else {
+                return Ok(());
+            };
+            self.product()?;
+            self.binary(op);
+        }
+    }
+
+    /// `signed (('*' | '/')? signed)*`; a name or `(` right after a factor
+    /// multiplies it, as in `2z` or `(z + 1)(z - 1)`.
+    fn product(&mut self) -> Result<(), String> {
+        self.signed()?;
+        loop {
+            let op = if self.eat('*') {
+                Op::Mul
+            } else if self.eat('/') {
+                Op::Div
+            } else if matches!(self.peek(), Some((_, c)) if c == '(' || c.is_alphabetic()) {
+                Op::Mul
+            } else {
+                return Ok(());
+            };
+            self.signed()?;
+            self.binary(op);
+        }
+    }
+
+    /// `'-'* power`; the sign binds looser than `^`, so `-z^2` is `-(z^2)`.
+    /// Signs are counted rather than recursed into, and cancel in pairs.
+    fn signed(&mut self) -> Result<(), String> {
+        let mut negated = false;
+        while self.eat('-') {
+            negated = !negated;
+        }
+        self.power()?;
+        if negated {
+            self.unary(Op::Negate);
+        }
+        Ok(())
+    }
+
+    /// `atom ('^' signed)?`, so powers group to the right and take signed exponents.
+    fn power(&mut self) -> Result<(), String> {
+        self.atom()?;
+        if let Some((column, '^')) = self.peek() {
+            self.chars.next();
+            self.nested(column, |compiler| compiler.signed())?;
+            self.binary(Op::Pow);
+        }
+        Ok(())
+    }
+
+    /// A number, a name, a function call or a parenthesized sum.
+    fn atom(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some((column, '(')) => {
+                self.chars.next();
+                self.nested(column, |compiler| compiler.sum())?;
+                if !self.eat(')') {
+                    return Err(format!("'(' at column {} is never closed", column + 1));
+                }
+                Ok(())
+            }
+            Some((column, c)) if c == '.' || c.is_ascii_digit() => {
+                let value = self.number(column)?;
+                self.push(Op::Constant((value, 0.0)))
+            }
+            Some((column, c)) if c.is_alphabetic() => {
+                let name = self.name();
+                self.named(&name, column)
+            }
+            Some((column, c)) => Err(format!("unexpected '{c}' at column {}", column + 1)),
+            None => Err("formula ends too early".to_string()),
+        }
+    }
+
+    /// Runs `parse` one level of nesting deeper.
+    fn nested(
+        &mut self,
+        column: usize,
+        parse: impl FnOnce(&mut Self) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if self.nesting == MAX_NESTING {
+            return Err(format!("nesting too deep at column {}", column + 1));
+        }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| c.is_alphanumeric()) {
+            name.push(c);
+        }
+        name
+    }
+
+    /// Emits the variable, constant or function call `name`.
+    fn named(&mut self, name: &str, column: usize) -> Result<(), String> {
+        if let Some(function) = Function::named(name) {
+            if !self.eat('(') {
+                return Err(format!(
+                    "'{name}' at column {} needs an argument in parentheses",
+                    column + 1
+                ));
+            }
+            self.nested(column, |compiler| compiler.sum())?;
+            if !self.eat(')') {
+                return Err(format!(
+                    "call of '{name}' at column {} is never closed",
+                    column + 1
+                ));
+            }
+            self.unary(Op::Call(function));
+            return Ok(());
+        }
+
+        let op = match name {
+            "z" => Op::Z,
+            "c" => Op::C,
+            "i" => Op::Constant((0.0, 1.0)),
+            "pi" => Op::Constant((std::f64::consts::PI, 0.0)),
+            "e" => Op::Constant((std::f64::consts::E, 0.0)),
+            _ => match name
+                .strip_prefix('p')
+                .and_then(|digits| digits.parse::<usize>().ok())
+            {
+                Some(number) if (1..=MAX_PARAMETERS).contains(&number) => {
+                    self.parameter_count = self.parameter_count.max(number);
+                    Op::Parameter(number - 1)
+                }
+                _ => {
+                    return Err(format!(
+                        "unknown name '{name}' at column {}; use z, c, i, pi, e, p1 to \
+                         p{MAX_PARAMETERS} or a function such as sin(z)",
+                        column + 1
+                    ))
+                }
+            },
+        };
+        self.push(op)
+    }
+
+    /// Digits with an optional fraction and exponent.
+    fn number(&mut self, column: usize) -> Result<f64, String> {
+        let mut text = String::new();
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| c == '.' || c.is_ascii_digit()) {
+            text.push(c);
+        }
+        // An exponent only if digits follow, so `2e` still reads as `2 * e`.
+        let mut lookahead = self.chars.clone();
+        let has_exponent = lookahead.next_if(|&(_, c)| c == 'e' || c == 'E').is_some() && {
+            lookahead.next_if(|&(_, c)| c == '+' || c == '-');
+            lookahead.peek().is_some_and(|(_, c)| c.is_ascii_digit())
+        };
+        if has_exponent {
+            text.extend(self.chars.next().map(|(_, c)| c));
+            text.extend(
+                self.chars
+                    .next_if(|&(_, c)| c == '+' || c == '-')
+                    .map(|(_, c)| c),
+            );
+            while let Some((_, c)) = self.chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+                text.push(c);
+            }
+        }
+        text.parse()
+            .map_err(|_| format!("invalid number '{text}' at column {}", column + 1))
+    }
+}
+
+/// A typed-in formula with the point as `c`, starting from the value of a
+/// second expression in `c` (where `z` is 0).
+pub struct Custom {
+    pub iteration: Expression,
+    pub start: Expression,
+    pub parameters: Vec<f64>,
+}
+
+impl Formula for Custom {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        let start = self.start.evaluate((0.0, 0.0), point, &self.parameters);
+        (start, point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        self.iteration.evaluate(z, c, &self.parameters)
+    }
+
+    /// Orbits that went to NaN, e.g. by dividing by zero, count as escaped.
+    fn escaped(&self, z: Complex, escape_radius_sq: f64) -> bool {
+        let radius_sq = norm_sq(z);
+        radius_sq.is_nan() || radius_sq > escape_radius_sq
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations_unless_undefined(iter, z)
+    }
+}
+
+/// The Julia sets of a typed-in formula, iterated from the point.
+pub struct CustomJulia {
+    pub iteration: Expression,
+    pub parameters: Vec<f64>,
+    pub c: Complex,
+}
+
+impl Formula for CustomJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        self.iteration.evaluate(z, c, &self.parameters)
+    }
+
+    fn escaped(&self, z: Complex, escape_radius_sq: f64) -> bool {
+        let radius_sq = norm_sq(z);
+        radius_sq.is_nan() || radius_sq > escape_radius_sq
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations_unless_undefined(iter, z)
+    }
+}
+
+/// The degree-2 smoothing for orbits that escaped to a finite value; the
+/// plain count for those that jumped to infinity or NaN, where it is undefined.
+fn smooth_iterations_unless_undefined(iter: u32, z: Complex) -> f64 {
+    if norm_sq(z).is_finite() {
+        smooth_iterations(iter, z.0, z.1)
+    } else {
+        iter as f64
+    }
+}
@@ -0,0 +1,625 @@
+//! Escape-time formulas: how the point under a pixel becomes an orbit.
+//!
+//! A [`Formula`] only describes the iteration; the shared loop in
+//! [`crate::fractal`] handles escape, convergence, cycle detection, smoothing
+//! and distance estimates, and remembers the previous point for formulas that
+//! look back at it. The renderer calls [`Formula::iterate`] once per pixel, so the
+//! per-step methods are dispatched statically inside it.
+
+use crate::collatz::{Collatz, CollatzJulia};
+pub use crate::complex::Complex;
+use crate::complex::{add, mul, pow, scale};
+use crate::expression::{Custom, CustomJulia};
+use crate::fractal::{self, orbit, smooth_iterations_for_power, Sample};
+use crate::interior;
+use crate::lyapunov::Lyapunov;
+use crate::magnet::{Magnet, MagnetJulia, MagnetMandelbrot};
+use crate::newton::{Newton, Nova, NovaJulia};
+use crate::params::MandelbrotParams;
+use crate::phoenix::{Phoenix, PhoenixJulia};
+
+pub trait Formula: Send + Sync {
+    /// Starting value `z` and parameter `c` of the orbit of `point`.
+    fn initial(&self, point: Complex) -> (Complex, Complex);
+
+    /// One step of the orbit.
+    fn step(&self, z: Complex, c: Complex) -> Complex;
+
+    /// One step of the orbit for formulas that also look back at the point
+    /// before `z`, `previous`, which is 0 on the first step. The orbit loops
+    /// call this one; the default forgets `previous` and takes [`Formula::step`].
+    fn step_with_previous(&self, z: Complex, previous: Complex, c: Complex) -> Complex {
+        let _ = previous;
+        self.step(z, c)
+    }
+
+    /// Whether the orbit has left for good.
+    fn escaped(&self, z: Complex, escape_radius_sq: f64) -> bool {
+        z.0 * z.0 + z.1 * z.1 > escape_radius_sq
+    }
+
+    /// Whether the orbit has settled on a finite attractor that is colored
+    /// like an escape, by the steps it took to get there. Checked after every
+    /// step; nothing converges by default.
+    fn converged(&self, z: Complex) -> bool {
+        let _ = z;
+        false
+    }
+
+    /// Derivative of the orbit with respect to the point before the first
+    /// step, or `None` if the formula has no derivative (and so no distance
+    /// estimates).
+    fn initial_derivative(&self) -> Option<Complex> {
+        None
+    }
+
+    /// Advances the derivative `dz` by one step from `z`. Only called when
+    /// [`Formula::initial_derivative`] gives a value.
+    fn step_derivative(&self, z: Complex, dz: Complex) -> Complex {
+        let _ = z;
+        dz
+    }
+
+    /// Smooth iteration count for an orbit that escaped to `z` after `iter`
+    /// steps. The default suits formulas of degree 2.
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        fractal::smooth_iterations(iter, z.0, z.1)
+    }
+
+    /// Sample for an orbit that ended at `z` without escaping, near a cycle
+    /// of `period` (0 if none was detected), when distances were requested.
+    fn interior(&self, z: Complex, c: Complex, period: u32, max_iter: u32) -> Sample {
+        let _ = (z, c);
+        Sample::interior(max_iter, period)
+    }
+
+    /// Iterates the orbit of `point`, with distance estimates if `distance` is
+    /// set and the formula has a derivative.
+    fn iterate(
+        &self,
+        point: Complex,
+        max_iter: u32,
+        escape_radius_sq: f64,
+        distance: bool,
+    ) -> Sample {
+        let (z, c) = self.initial(point);
+        if distance && self.initial_derivative().is_some() {
+            orbit::<Self, true>(self, z, c, max_iter, escape_radius_sq)
+        } else {
+            orbit::<Self, false>(self, z, c, max_iter, escape_radius_sq)
+        }
+    }
+}
+
+/// `z^2 + c`, in the operation order the vectorized kernel uses as well.
+fn square_plus(z: Complex, c: Complex) -> Complex {
+    (z.0 * z.0 - z.1 * z.1 + c.0, 2.0 * z.0 * z.1 + c.1)
+}
+
+/// `2 z dz + increment`, the derivative of [`square_plus`].
+fn square_derivative(z: Complex, dz: Complex, increment: f64) -> Complex {
+    (
+        2.0 * (z.0 * dz.0 - z.1 * dz.1) + increment,
+        2.0 * (z.0 * dz.1 + z.1 * dz.0),
+    )
+}
+
+/// The Mandelbrot set: `z^2 + c` from `z = 0`, with the point as `c`.
+pub struct Mandelbrot;
+
+impl Formula for Mandelbrot {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        square_plus(z, c)
+    }
+
+    fn initial_derivative(&self) -> Option<Complex> {
+        Some((0.0, 0.0))
+    }
+
+    fn step_derivative(&self, z: Complex, dz: Complex) -> Complex {
+        square_derivative(z, dz, 1.0)
+    }
+
+    fn interior(&self, z: Complex, c: Complex, period: u32, max_iter: u32) -> Sample {
+        interior::cycle_sample(z, c, period, max_iter, true)
+    }
+
+    /// Adds the closed-form cardioid and bulb test to the shared loop.
+    fn iterate(
+        &self,
+        point: Complex,
+        max_iter: u32,
+        escape_radius_sq: f64,
+        distance: bool,
+    ) -> Sample {
+        if distance {
+            fractal::mandelbrot_distance(point.0, point.1, max_iter, escape_radius_sq)
+        } else {
+            fractal::mandelbrot_iterations(point.0, point.1, max_iter, escape_radius_sq)
+        }
+    }
+}
+
+/// The Julia set of `c`: `z^2 + c` from the point.
+pub struct Julia {
+    pub c: Complex,
+}
+
+impl Formula for Julia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        square_plus(z, c)
+    }
+
+    fn initial_derivative(&self) -> Option<Complex> {
+        Some((1.0, 0.0))
+    }
+
+    fn step_derivative(&self, z: Complex, dz: Complex) -> Complex {
+        square_derivative(z, dz, 0.0)
+    }
+
+    fn interior(&self, z: Complex, c: Complex, period: u32, max_iter: u32) -> Sample {
+        interior::cycle_sample(z, c, period, max_iter, false)
+    }
+}
+
+/// Multibrot sets: `z^d + c` with the point as `c`, for any real power `d`.
+///
+/// The orbit starts at the critical point 0. For negative powers 0 maps to
+/// infinity, and infinity to `c`, so the orbit starts at `c` instead.
+pub struct Multibrot {
+    pub power: f64,
+}
+
+impl Formula for Multibrot {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        if self.power < 0.0 {
+            (point, point)
+        } else {
+            ((0.0, 0.0), point)
+        }
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        add(pow(z, self.power), c)
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations_for_power(iter, z.0, z.1, self.power)
+    }
+
+    /// Distance estimates assume orbits escape by repeated powering, so
+    /// they are only offered for `d > 1`.
+    fn initial_derivative(&self) -> Option<Complex> {
+        (self.power > 1.0).then_some((0.0, 0.0))
+    }
+
+    fn step_derivative(&self, z: Complex, dz: Complex) -> Complex {
+        add(power_derivative(z, dz, self.power), (1.0, 0.0))
+    }
+}
+
+/// Julia sets of `z^d + c`, iterated from the point.
+pub struct MultibrotJulia {
+    pub power: f64,
+    pub c: Complex,
+}
+
+impl Formula for MultibrotJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        add(pow(z, self.power), c)
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations_for_power(iter, z.0, z.1, self.power)
+    }
+
+    fn initial_derivative(&self) -> Option<Complex> {
+        (self.power > 1.0).then_some((1.0, 0.0))
+    }
+
+    fn step_derivative(&self, z: Complex, dz: Complex) -> Complex {
+        power_derivative(z, dz, self.power)
+    }
+}
+
+/// `d z^(d-1) dz`, the derivative of `z^d`.
+fn power_derivative(z: Complex, dz: Complex, power: f64) -> Complex {
+    scale(mul(pow(z, power - 1.0), dz), power)
+}
+
+/// Variants of `z^2 + c` that fold or mirror the orbit with absolute values
+/// and conjugation. None of them is holomorphic, so they have no derivative
+/// for distance estimates, and no interior analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// `(|x| + i|y|)^2`.
+    BurningShip,
+    /// `conj(z)^2`, also known as the Mandelbar set.
+    Tricorn,
+    /// `|x^2 - y^2| + 2ixy`.
+    Celtic,
+    /// `|x^2 - y^2| + 2i|xy|`: the Celtic fold applied to the Burning Ship.
+    Buffalo,
+    /// `(|x| - iy)^2`.
+    Perpendicular,
+}
+
+impl Variant {
+    /// The variant's replacement for `z^2`.
+    fn square(self, (x, y): Complex) -> Complex {
+        let real = x * x - y * y;
+        match self {
+            Variant::BurningShip => (real, 2.0 * (x * y).abs()),
+            Variant::Tricorn => (real, -2.0 * x * y),
+            Variant::Celtic => (real.abs(), 2.0 * x * y),
+            Variant::Buffalo => (real.abs(), 2.0 * (x * y).abs()),
+            Variant::Perpendicular => (real, -2.0 * x.abs() * y),
+        }
+    }
+}
+
+/// A [`Variant`] iterated from 0 with the point as `c`.
+pub struct VariantMandelbrot {
+    pub variant: Variant,
+}
+
+impl Formula for VariantMandelbrot {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        add(self.variant.square(z), c)
+    }
+}
+
+/// The Julia sets of a [`Variant`], iterated from the point.
+pub struct VariantJulia {
+    pub variant: Variant,
+    pub c: Complex,
+}
+
+impl Formula for VariantJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        add(self.variant.square(z), c)
+    }
+}
+
+/// The formulas to pick from, as stored in the view parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormulaKind {
+    Mandelbrot,
+    Julia,
+    Multibrot,
+    MultibrotJulia,
+    BurningShip,
+    BurningShipJulia,
+    Tricorn,
+    TricornJulia,
+    Celtic,
+    CelticJulia,
+    Buffalo,
+    BuffaloJulia,
+    Perpendicular,
+    PerpendicularJulia,
+    Newton,
+    Nova,
+    NovaJulia,
+    Custom,
+    CustomJulia,
+    Lyapunov,
+    Phoenix,
+    PhoenixJulia,
+    MagnetI,
+    MagnetIJulia,
+    MagnetII,
+    MagnetIIJulia,
+    Collatz,
+    CollatzJulia,
+}
+
+impl FormulaKind {
+    pub const ALL: [FormulaKind; 28] = [
+        FormulaKind::Mandelbrot,
+        FormulaKind::Julia,
+        FormulaKind::Multibrot,
+        FormulaKind::MultibrotJulia,
+        FormulaKind::BurningShip,
+        FormulaKind::BurningShipJulia,
+        FormulaKind::Tricorn,
+        FormulaKind::TricornJulia,
+        FormulaKind::Celtic,
+        FormulaKind::CelticJulia,
+        FormulaKind::Buffalo,
+        FormulaKind::BuffaloJulia,
+        FormulaKind::Perpendicular,
+        FormulaKind::PerpendicularJulia,
+        FormulaKind::Newton,
+        FormulaKind::Nova,
+        FormulaKind::NovaJulia,
+        FormulaKind::Custom,
+        FormulaKind::CustomJulia,
+        FormulaKind::Lyapunov,
+        FormulaKind::Phoenix,
+        FormulaKind::PhoenixJulia,
+        FormulaKind::MagnetI,
+        FormulaKind::MagnetIJulia,
+        FormulaKind::MagnetII,
+        FormulaKind::MagnetIIJulia,
+        FormulaKind::Collatz,
+        FormulaKind::CollatzJulia,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FormulaKind::Mandelbrot => "Mandelbrot",
+            FormulaKind::Julia => "Julia",
+            FormulaKind::Multibrot => "Multibrot",
+            FormulaKind::MultibrotJulia => "Multibrot Julia",
+            FormulaKind::BurningShip => "Burning Ship",
+            FormulaKind::BurningShipJulia => "Burning Ship Julia",
+            FormulaKind::Tricorn => "Tricorn",
+            FormulaKind::TricornJulia => "Tricorn Julia",
+            FormulaKind::Celtic => "Celtic",
+            FormulaKind::CelticJulia => "Celtic Julia",
+            FormulaKind::Buffalo => "Buffalo",
+            FormulaKind::BuffaloJulia => "Buffalo Julia",
+            FormulaKind::Perpendicular => "Perpendicular",
+            FormulaKind::PerpendicularJulia => "Perpendicular Julia",
+            FormulaKind::Newton => "Newton",
+            FormulaKind::Nova => "Nova",
+            FormulaKind::NovaJulia => "Nova Julia",
+            FormulaKind::Custom => "Custom",
+            FormulaKind::CustomJulia => "Custom Julia",
+            FormulaKind::Lyapunov => "Lyapunov",
+            FormulaKind::Phoenix => "Phoenix",
+            FormulaKind::PhoenixJulia => "Phoenix Julia",
+            FormulaKind::MagnetI => "Magnet I",
+            FormulaKind::MagnetIJulia => "Magnet I Julia",
+            FormulaKind::MagnetII => "Magnet II",
+            FormulaKind::MagnetIIJulia => "Magnet II Julia",
+            FormulaKind::Collatz => "Collatz",
+            FormulaKind::CollatzJulia => "Collatz Julia",
+        }
+    }
+
+    /// Whether the formula iterates the point with the Julia constant as `c`.
+    pub fn is_julia(self) -> bool {
+        match self {
+            FormulaKind::Julia
+            | FormulaKind::MultibrotJulia
+            | FormulaKind::NovaJulia
+            | FormulaKind::CustomJulia
+            | FormulaKind::PhoenixJulia
+            | FormulaKind::MagnetIJulia
+            | FormulaKind::MagnetIIJulia
+            | FormulaKind::CollatzJulia => true,
+            _ => self.variant().is_some_and(|(_, julia)| julia),
+        }
+    }
+
+    /// Whether the formula takes the power `d` from the view parameters.
+    pub fn uses_power(self) -> bool {
+        matches!(self, FormulaKind::Multibrot | FormulaKind::MultibrotJulia)
+    }
+
+    /// Whether the formula iterates the expression typed into the view parameters.
+    pub fn is_custom(self) -> bool {
+        matches!(self, FormulaKind::Custom | FormulaKind::CustomJulia)
+    }
+
+    /// Whether the formula runs Newton's method on the polynomial in the
+    /// view parameters, following orbits until they converge rather than escape.
+    pub fn is_newton(self) -> bool {
+        matches!(
+            self,
+            FormulaKind::Newton | FormulaKind::Nova | FormulaKind::NovaJulia
+        )
+    }
+
+    /// Whether the points that never escape form a set without holes, as
+    /// they do for polynomials `z^d + c`, so Mariani–Silver subdivision may
+    /// fill a rectangle from its border. Poles, memory terms and folds break this.
+    pub fn has_filled_interior(self) -> bool {
+        matches!(
+            self,
+            FormulaKind::Mandelbrot
+                | FormulaKind::Julia
+                | FormulaKind::Multibrot
+                | FormulaKind::MultibrotJulia
+        )
+    }
+
+    /// Whether the formula adds the Phoenix memory term `p` from the view parameters.
+    pub fn is_phoenix(self) -> bool {
+        matches!(self, FormulaKind::Phoenix | FormulaKind::PhoenixJulia)
+    }
+
+    /// The magnet map this formula iterates, if any.
+    pub fn magnet(self) -> Option<Magnet> {
+        match self {
+            FormulaKind::MagnetI | FormulaKind::MagnetIJulia => Some(Magnet::One),
+            FormulaKind::MagnetII | FormulaKind::MagnetIIJulia => Some(Magnet::Two),
+            _ => None,
+        }
+    }
+
+    /// Whether orbits escape at the escape radius of the view parameters.
+    /// Newton orbits converge instead, Lyapunov orbits run a fixed count and
+    /// the magnet maps bring their own radius.
+    pub fn uses_escape_radius(self) -> bool {
+        !(self.is_newton() || self.is_lyapunov() || self.magnet().is_some())
+    }
+
+    /// Whether the formula draws the Lyapunov exponent of the logistic map,
+    /// over the plane of its two rates `(a, b)` rather than the complex plane.
+    pub fn is_lyapunov(self) -> bool {
+        self == FormulaKind::Lyapunov
+    }
+
+    /// Names of the horizontal and vertical axes of the plane the formula is drawn over.
+    pub fn axis_names(self) -> [&'static str; 2] {
+        if self.is_lyapunov() {
+            ["a", "b"]
+        } else {
+            ["Real", "Imaginary"]
+        }
+    }
+
+    /// Whether this is `z^2 + c`, which has vectorized, double-double and
+    /// perturbation kernels. Other formulas render through [`Formula`] at `f64`.
+    pub fn is_quadratic(self) -> bool {
+        matches!(self, FormulaKind::Mandelbrot | FormulaKind::Julia)
+    }
+
+    /// The [`Variant`] this formula iterates, and whether in its Julia form.
+    pub fn variant(self) -> Option<(Variant, bool)> {
+        let variant = match self {
+            FormulaKind::BurningShip => (Variant::BurningShip, false),
+            FormulaKind::BurningShipJulia => (Variant::BurningShip, true),
+            FormulaKind::Tricorn => (Variant::Tricorn, false),
+            FormulaKind::TricornJulia => (Variant::Tricorn, true),
+            FormulaKind::Celtic => (Variant::Celtic, false),
+            FormulaKind::CelticJulia => (Variant::Celtic, true),
+            FormulaKind::Buffalo => (Variant::Buffalo, false),
+            FormulaKind::BuffaloJulia => (Variant::Buffalo, true),
+            FormulaKind::Perpendicular => (Variant::Perpendicular, false),
+            FormulaKind::PerpendicularJulia => (Variant::Perpendicular, true),
+            FormulaKind::Mandelbrot
+            | FormulaKind::Julia
+            | FormulaKind::Multibrot
+            | FormulaKind::MultibrotJulia
+            | FormulaKind::Newton
+            | FormulaKind::Nova
+            | FormulaKind::NovaJulia
+            | FormulaKind::Custom
+            | FormulaKind::CustomJulia
+            | FormulaKind::Lyapunov
+            | FormulaKind::Phoenix
+            | FormulaKind::PhoenixJulia
+            | FormulaKind::MagnetI
+            | FormulaKind::MagnetIJulia
+            | FormulaKind::MagnetII
+            | FormulaKind::MagnetIIJulia
+            | FormulaKind::Collatz
+            | FormulaKind::CollatzJulia => return None,
+        };
+        Some(variant)
+    }
+
+    /// The formula, set up with the constants in `params` it uses.
+    pub fn build(self, params: &MandelbrotParams) -> Box<dyn Formula> {
+        let julia_c = (params.julia_c_real, params.julia_c_imag);
+        let phoenix_p = (params.phoenix_p_real, params.phoenix_p_imag);
+        match self {
+            FormulaKind::Mandelbrot => Box::new(Mandelbrot),
+            FormulaKind::Julia => Box::new(Julia { c: julia_c }),
+            FormulaKind::Multibrot => Box::new(Multibrot {
+                power: params.power,
+            }),
+            FormulaKind::MultibrotJulia => Box::new(MultibrotJulia {
+                power: params.power,
+                c: julia_c,
+            }),
+            FormulaKind::Newton => {
+                Box::new(Newton::new(params.polynomial.clone(), params.relaxation))
+            }
+            FormulaKind::Nova => Box::new(Nova::new(params.polynomial.clone(), params.relaxation)),
+            FormulaKind::NovaJulia => Box::new(NovaJulia {
+                polynomial: params.polynomial.clone(),
+                relaxation: params.relaxation,
+                c: julia_c,
+            }),
+            FormulaKind::Custom => Box::new(Custom {
+                iteration: params.expression.clone(),
+                start: params.start_expression.clone(),
+                parameters: params.expression_parameters.clone(),
+            }),
+            FormulaKind::CustomJulia => Box::new(CustomJulia {
+                iteration: params.expression.clone(),
+                parameters: params.expression_parameters.clone(),
+                c: julia_c,
+            }),
+            FormulaKind::Lyapunov => Box::new(Lyapunov {
+                sequence: params.lyapunov_sequence.clone(),
+            }),
+            FormulaKind::Phoenix => Box::new(Phoenix { p: phoenix_p }),
+            FormulaKind::PhoenixJulia => Box::new(PhoenixJulia {
+                p: phoenix_p,
+                c: julia_c,
+            }),
+            FormulaKind::MagnetI | FormulaKind::MagnetII => Box::new(MagnetMandelbrot {
+                magnet: self.magnet().expect("magnet formulas have a magnet map"),
+            }),
+            FormulaKind::MagnetIJulia | FormulaKind::MagnetIIJulia => Box::new(MagnetJulia {
+                magnet: self.magnet().expect("magnet formulas have a magnet map"),
+                c: julia_c,
+            }),
+            FormulaKind::Collatz => Box::new(Collatz),
+            FormulaKind::CollatzJulia => Box::new(CollatzJulia { c: julia_c }),
+            _ => match self.variant() {
+                Some((variant, false)) => Box::new(VariantMandelbrot { variant }),
+                Some((variant, true)) => Box::new(VariantJulia {
+                    variant,
+                    c: julia_c,
+                }),
+                None => unreachable!("every other formula is a variant"),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for FormulaKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mandelbrot" => Ok(FormulaKind::Mandelbrot),
+            "julia" => Ok(FormulaKind::Julia),
+            "multibrot" => Ok(FormulaKind::Multibrot),
+            "multibrot-julia" => Ok(FormulaKind::MultibrotJulia),
+            "burning-ship" => Ok(FormulaKind::BurningShip),
+            "burning-ship-julia" => Ok(FormulaKind::BurningShipJulia),
+            "tricorn" => Ok(FormulaKind::Tricorn),
+            "tricorn-julia" => Ok(FormulaKind::TricornJulia),
+            "celtic" => Ok(FormulaKind::Celtic),
+            "celtic-julia" => Ok(FormulaKind::CelticJulia),
+            "buffalo" => Ok(FormulaKind::Buffalo),
+            "buffalo-julia" => Ok(FormulaKind::BuffaloJulia),
+            "perpendicular" => Ok(FormulaKind::Perpendicular),
+            "perpendicular-julia" => Ok(FormulaKind::PerpendicularJulia),
+            "newton" => Ok(FormulaKind::Newton),
+            "nova" => Ok(FormulaKind::Nova),
+            "nova-julia" => Ok(FormulaKind::NovaJulia),
+            "custom" => Ok(FormulaKind::Custom),
+            "custom-julia" => Ok(FormulaKind::CustomJulia),
+            "lyapunov" => Ok(FormulaKind::Lyapunov),
+            "phoenix" => Ok(FormulaKind::Phoenix),
+            "phoenix-julia" => Ok(FormulaKind::PhoenixJulia),
+            "magnet-1" => Ok(FormulaKind::MagnetI),
+            "magnet-1-julia" => Ok(FormulaKind::MagnetIJulia),
+            "magnet-2" => Ok(FormulaKind::MagnetII),
+            "magnet-2-julia" => Ok(FormulaKind::MagnetIIJulia),
+            "collatz" => Ok(FormulaKind::Collatz),
+            "collatz-julia" => Ok(FormulaKind::CollatzJulia),
+            _ => Err(format!("unknown formula: {s}")),
+        }
+    }
+}
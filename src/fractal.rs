@@ -0,0 +1,394 @@
+use crate::complex::Complex;
+use crate::double_double::DoubleDouble;
+use crate::formula::{Formula, Julia, Mandelbrot};
+use crate::interior;
+
+/// Outcome of iterating a single point.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sample {
+    /// Smooth iteration count; `max_iter` for points that never escaped.
+    pub iterations: f64,
+    /// Period of the attracting cycle the orbit settled into, or 0 if none was detected.
+    pub period: u32,
+    /// Estimated distance to the boundary in complex-plane units. Infinite for
+    /// escaped points and 0 for the others, unless an estimate was requested.
+    pub distance: f64,
+    /// Multiplier of the attracting cycle, or 0 if the cycle was not located.
+    pub multiplier: (f64, f64),
+    /// Index of the root a Newton orbit converged to, for formulas with roots.
+    pub root: Option<u32>,
+    /// Lyapunov exponent of the orbit, for the Lyapunov formula.
+    pub exponent: Option<f64>,
+}
+
+impl Sample {
+    pub fn escaped(iterations: f64) -> Self {
+        Self {
+            iterations,
+            period: 0,
+            distance: f64::INFINITY,
+            multiplier: (0.0, 0.0),
+            root: None,
+            exponent: None,
+        }
+    }
+
+    pub fn interior(max_iter: u32, period: u32) -> Self {
+        Self {
+            iterations: max_iter as f64,
+            period,
+            distance: 0.0,
+            multiplier: (0.0, 0.0),
+            root: None,
+            exponent: None,
+        }
+    }
+
+    /// A point whose orbit converged, to `root` if it is known.
+    pub fn converged(iterations: f64, root: Option<u32>) -> Self {
+        Self {
+            root,
+            ..Self::escaped(iterations)
+        }
+    }
+
+    /// A point of a Lyapunov fractal, whose orbit has this exponent.
+    pub fn lyapunov(exponent: f64) -> Self {
+        Self {
+            exponent: Some(exponent),
+            ..Self::escaped(0.0)
+        }
+    }
+
+    /// An escaped point with its distance estimate.
+    pub fn escaped_at_distance(iterations: f64, distance: f64) -> Self {
+        Self {
+            distance,
+            ..Self::escaped(iterations)
+        }
+    }
+}
+
+/// Exterior distance estimate `|z| ln|z| / |dz|` for an orbit that escaped to `z`.
+pub fn distance_estimate(zr: f64, zi: f64, dzr: f64, dzi: f64) -> f64 {
+    let mag = (zr * zr + zi * zi).sqrt();
+    mag * mag.ln() / (dzr * dzr + dzi * dzi).sqrt()
+}
+
+/// Two orbit points closer than this (per component) are treated as the same point.
+pub(crate) const CYCLE_EPSILON: f64 = 1e-14;
+
+/// Whether `a` and `b` are the same point to within [`CYCLE_EPSILON`].
+fn close(a: Complex, b: Complex) -> bool {
+    (a.0 - b.0).abs() < CYCLE_EPSILON && (a.1 - b.1).abs() < CYCLE_EPSILON
+}
+
+pub fn mandelbrot_iterations(
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    if in_main_cardioid_or_bulb(c_real, c_imag) {
+        return Sample::interior(max_iter, 0);
+    }
+
+    orbit::<_, false>(
+        &Mandelbrot,
+        (0.0, 0.0),
+        (c_real, c_imag),
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// [`mandelbrot_iterations`] that also estimates distances to the boundary:
+/// from outside for escaping points, and from inside for points whose
+/// attracting cycle is located, which also gives its multiplier.
+pub fn mandelbrot_distance(
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    if in_main_cardioid_or_bulb(c_real, c_imag) {
+        let (z, period) = interior::main_cycle_point((c_real, c_imag));
+        return interior::cycle_sample(z, (c_real, c_imag), period, max_iter, true);
+    }
+
+    orbit::<_, true>(
+        &Mandelbrot,
+        (0.0, 0.0),
+        (c_real, c_imag),
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// Whether `c` lies in the main cardioid or the period-2 bulb of the Mandelbrot set.
+///
+/// Both regions have closed forms, so their points never need to be iterated.
+pub fn in_main_cardioid_or_bulb(c_real: f64, c_imag: f64) -> bool {
+    let y_sq = c_imag * c_imag;
+
+    let x = c_real - 0.25;
+    let q = x * x + y_sq;
+    if q * (q + x) <= 0.25 * y_sq {
+        return true;
+    }
+
+    let x = c_real + 1.0;
+    x * x + y_sq <= 0.0625
+}
+
+pub fn julia_iterations(
+    z_real: f64,
+    z_imag: f64,
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    let c = (c_real, c_imag);
+    orbit::<_, false>(
+        &Julia { c },
+        (z_real, z_imag),
+        c,
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// [`julia_iterations`] that also estimates the distance of escaping points to
+/// the set. Points that do not escape get the multiplier of their attracting
+/// cycle when it is located, but no distance.
+pub fn julia_distance(
+    z_real: f64,
+    z_imag: f64,
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    let c = (c_real, c_imag);
+    orbit::<_, true>(
+        &Julia { c },
+        (z_real, z_imag),
+        c,
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// Iterates `formula` from `z`, stopping early once the orbit is caught in a cycle.
+///
+/// Cycles are found with Brent's algorithm: the orbit is compared against a
+/// saved point that is refreshed after 1, 2, 4, 8, ... steps, so any period is
+/// detected within a small multiple of its length at the cost of one compare.
+/// The point before it is saved and compared as well, as formulas that step
+/// from both only repeat once the pair does.
+///
+/// Orbits that [`Formula::converged`] stop there, with their plain step count.
+///
+/// With `DISTANCE` the derivative is carried along for a distance estimate
+/// and bounded orbits go to [`Formula::interior`]; without it the derivative
+/// is never touched.
+pub(crate) fn orbit<F: Formula + ?Sized, const DISTANCE: bool>(
+    formula: &F,
+    mut z: Complex,
+    c: Complex,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    let mut dz = formula.initial_derivative().unwrap_or_default();
+    let mut previous = (0.0, 0.0);
+    let mut iter = 0;
+    let mut saved = (z, previous);
+    let mut steps_since_save = 0;
+    let mut save_interval = 1;
+
+    while !formula.escaped(z, escape_radius_sq) && iter < max_iter {
+        if DISTANCE {
+            dz = formula.step_derivative(z, dz);
+        }
+        (z, previous) = (formula.step_with_previous(z, previous, c), z);
+        iter += 1;
+        if formula.converged(z) {
+            return Sample::converged(iter as f64, None);
+        }
+
+        steps_since_save += 1;
+        if close(z, saved.0) && close(previous, saved.1) {
+            if DISTANCE {
+                return formula.interior(z, c, steps_since_save, max_iter);
+            }
+            return Sample::interior(max_iter, steps_since_save);
+        }
+        if steps_since_save == save_interval {
+            saved = (z, previous);
+            steps_since_save = 0;
+            save_interval = save_interval.saturating_mul(2);
+        }
+    }
+
+    if iter >= max_iter {
+        if DISTANCE {
+            // No cycle showed up in time; the orbit may still be converging to one.
+            return formula.interior(z, c, 0, max_iter);
+        }
+        Sample::interior(max_iter, 0)
+    } else if DISTANCE {
+        Sample::escaped_at_distance(
+            formula.smooth_iterations(iter, z),
+            distance_estimate(z.0, z.1, dz.0, dz.1),
+        )
+    } else {
+        Sample::escaped(formula.smooth_iterations(iter, z))
+    }
+}
+
+/// [`mandelbrot_iterations`] in double-double precision, for zooms past the `f64` limit.
+pub fn mandelbrot_iterations_dd(
+    c_real: DoubleDouble,
+    c_imag: DoubleDouble,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    mandelbrot_dd::<false>(c_real, c_imag, max_iter, escape_radius_sq)
+}
+
+/// [`mandelbrot_distance`] in double-double precision, estimating exterior
+/// distances only: interior cycles are located in `f64`, which cannot tell
+/// apart the points of a view this deep.
+pub fn mandelbrot_distance_dd(
+    c_real: DoubleDouble,
+    c_imag: DoubleDouble,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    mandelbrot_dd::<true>(c_real, c_imag, max_iter, escape_radius_sq)
+}
+
+fn mandelbrot_dd<const DISTANCE: bool>(
+    c_real: DoubleDouble,
+    c_imag: DoubleDouble,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    if in_main_cardioid_or_bulb(c_real.hi, c_imag.hi) {
+        return Sample::interior(max_iter, 0);
+    }
+
+    iterate_dd::<_, DISTANCE>(
+        &Mandelbrot,
+        (DoubleDouble::ZERO, DoubleDouble::ZERO),
+        (c_real, c_imag),
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// [`julia_iterations`] in double-double precision, for zooms past the `f64` limit.
+pub fn julia_iterations_dd(
+    z_real: DoubleDouble,
+    z_imag: DoubleDouble,
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    iterate_dd::<_, false>(
+        &Julia {
+            c: (c_real, c_imag),
+        },
+        (z_real, z_imag),
+        (
+            DoubleDouble::from_f64(c_real),
+            DoubleDouble::from_f64(c_imag),
+        ),
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// [`julia_distance`] in double-double precision, for escaping points only.
+pub fn julia_distance_dd(
+    z_real: DoubleDouble,
+    z_imag: DoubleDouble,
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    iterate_dd::<_, true>(
+        &Julia {
+            c: (c_real, c_imag),
+        },
+        (z_real, z_imag),
+        (
+            DoubleDouble::from_f64(c_real),
+            DoubleDouble::from_f64(c_imag),
+        ),
+        max_iter,
+        escape_radius_sq,
+    )
+}
+
+/// Iterates `z^2 + c` in double-double precision. Only the derivative comes
+/// from `formula`, which must be one of the quadratic formulas.
+///
+/// There is no cycle check: pixels here are far closer together than any
+/// fixed tolerance, so orbits that are still escaping would pass for cycles.
+fn iterate_dd<F: Formula, const DISTANCE: bool>(
+    formula: &F,
+    z: (DoubleDouble, DoubleDouble),
+    c: (DoubleDouble, DoubleDouble),
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> Sample {
+    let ((mut zr, mut zi), (c_real, c_imag)) = (z, c);
+    // The derivative only sets the scale of the estimate, so `f64` is plenty.
+    let mut dz = formula.initial_derivative().unwrap_or_default();
+    let mut iter = 0;
+
+    // The escape test only needs the leading parts.
+    while zr.hi * zr.hi + zi.hi * zi.hi <= escape_radius_sq && iter < max_iter {
+        if DISTANCE {
+            dz = formula.step_derivative((zr.hi, zi.hi), dz);
+        }
+
+        let zr_new = zr.square() - zi.square() + c_real;
+        zi = (zr * zi).double() + c_imag;
+        zr = zr_new;
+        iter += 1;
+    }
+
+    if iter >= max_iter {
+        Sample::interior(max_iter, 0)
+    } else if DISTANCE {
+        Sample::escaped_at_distance(
+            smooth_iterations(iter, zr.hi, zi.hi),
+            distance_estimate(zr.hi, zi.hi, dz.0, dz.1),
+        )
+    } else {
+        Sample::escaped(smooth_iterations(iter, zr.hi, zi.hi))
+    }
+}
+
+/// Smooth (continuous) iteration count for an orbit of `z^2 + c` that escaped
+/// to `z` after `iter` steps.
+pub fn smooth_iterations(iter: u32, zr: f64, zi: f64) -> f64 {
+    smooth_iterations_for_power(iter, zr, zi, 2.0)
+}
+
+/// [`smooth_iterations`] for `z^d + c`, where escaping orbits grow like
+/// `|z|^d` per step. Powers with `d <= 1` do not blow orbits up like that:
+/// negative ones escape by passing close to the pole at 0, by however much
+/// it throws them out. They get the plain count.
+pub fn smooth_iterations_for_power(iter: u32, zr: f64, zi: f64, power: f64) -> f64 {
+    if power <= 1.0 {
+        return iter as f64;
+    }
+    let mag = (zr * zr + zi * zi).sqrt();
+    iter as f64 + 1.0 - (mag.ln() / std::f64::consts::LN_2).ln() / power.ln()
+}
@@ -0,0 +1,34 @@
+//! Escape-time fractal engine behind the Mandelbrot explorer.
+//!
+//! The [`Renderer`] turns a [`MandelbrotParams`] view into a [`Frame`]
+//! holding smooth iteration counts and colored pixels, independent of any UI.
+
+pub mod bigfloat;
+pub mod collatz;
+pub mod color;
+pub mod complex;
+pub mod density;
+pub mod double_double;
+pub mod export;
+pub mod expression;
+pub mod formula;
+pub mod fractal;
+mod interior;
+pub mod lyapunov;
+pub mod magnet;
+pub mod newton;
+pub mod params;
+pub mod perturbation;
+pub mod phoenix;
+pub mod polynomial;
+pub mod renderer;
+pub mod simd;
+pub mod worker;
+
+pub use color::colorize_pixel;
+pub use formula::{Formula, FormulaKind};
+pub use fractal::{julia_iterations, mandelbrot_iterations, Sample};
+pub use params::{
+    Coloring, DensityPlot, InteriorColoring, MandelbrotParams, Precision, Supersampling,
+};
+pub use renderer::{Frame, Renderer, Tile};
@@ -0,0 +1,130 @@
+//! Lyapunov fractals of the logistic map `x -> r x (1 - x)`.
+//!
+//! The rate `r` alternates between the two coordinates `a` and `b` of the
+//! point following a sequence such as `AABAB`, repeated for the whole orbit.
+//! Each pixel shows the Lyapunov exponent of its orbit: negative where the
+//! orbit settles into a cycle, positive where it is chaotic.
+
+use std::fmt;
+
+use crate::complex::Complex;
+use crate::formula::Formula;
+use crate::fractal::Sample;
+
+/// Longest sequence accepted from text.
+pub const MAX_SEQUENCE_LENGTH: usize = 64;
+
+/// Steps taken before the exponent is measured, so the orbit has settled.
+pub const SETTLE_STEPS: u32 = 200;
+
+/// Starting value of every orbit; the critical point of the logistic map.
+const START: f64 = 0.5;
+
+/// Which coordinate each step of the logistic map takes its rate from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sequence {
+    /// `false` for `A`, `true` for `B`; never empty.
+    steps: Vec<bool>,
+}
+
+impl Sequence {
+    /// The rate of step `n` for the point `(a, b)`.
+    fn rate(&self, n: usize, (a, b): Complex) -> f64 {
+        if self.steps[n % self.steps.len()] {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+impl Default for Sequence {
+    /// `AB`, which draws the classic "Zircon Zity".
+    fn default() -> Self {
+        Self {
+            steps: vec![false, true],
+        }
+    }
+}
+
+impl fmt::Display for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &b in &self.steps {
+            f.write_str(if b { "B" } else { "A" })?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Sequence {
+    type Err = String;
+
+    /// Reads a string of `A`s and `B`s, in either case; spaces are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let steps = s
+            .char_indices()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(column, c)| match c.to_ascii_uppercase() {
+                'A' => Ok(false),
+                'B' => Ok(true),
+                _ => Err(format!("unexpected '{c}' at column {}", column + 1)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if steps.is_empty() {
+            return Err("the sequence needs at least one A or B".to_string());
+        }
+        if steps.len() > MAX_SEQUENCE_LENGTH {
+            return Err(format!(
+                "the sequence is longer than {MAX_SEQUENCE_LENGTH} steps"
+            ));
+        }
+        Ok(Self { steps })
+    }
+}
+
+/// The Lyapunov fractal for `sequence`, with the point as `(a, b)`.
+pub struct Lyapunov {
+    pub sequence: Sequence,
+}
+
+impl Lyapunov {
+    /// Lyapunov exponent of the orbit of `point`, averaged over `steps` steps
+    /// after [`SETTLE_STEPS`]. Orbits that run off to infinity count as
+    /// infinitely chaotic.
+    pub fn exponent(&self, point: Complex, steps: u32) -> f64 {
+        let mut x = START;
+        for n in 0..SETTLE_STEPS as usize {
+            x *= self.sequence.rate(n, point) * (1.0 - x);
+        }
+
+        let mut sum = 0.0;
+        for n in SETTLE_STEPS as usize..(SETTLE_STEPS + steps) as usize {
+            let rate = self.sequence.rate(n, point);
+            // The log of the derivative of this step.
+            sum += (rate * (1.0 - 2.0 * x)).abs().ln();
+            x *= rate * (1.0 - x);
+            if !x.is_finite() {
+                return f64::INFINITY;
+            }
+        }
+        sum / steps.max(1) as f64
+    }
+}
+
+impl Formula for Lyapunov {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((START, 0.0), point)
+    }
+
+    /// One pass through the whole sequence, with `x` as the real part of `z`.
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        let x = (0..self.sequence.steps.len())
+            .fold(z.0, |x, n| x * self.sequence.rate(n, c) * (1.0 - x));
+        (x, 0.0)
+    }
+
+    /// Measures the exponent over `max_iter` steps; nothing escapes.
+    fn iterate(&self, point: Complex, max_iter: u32, _: f64, _: bool) -> Sample {
+        Sample::lyapunov(self.exponent(point, max_iter))
+    }
+}
@@ -0,0 +1,122 @@
+//! Magnet fractals: the renormalization maps of the Ising model on
+//! hierarchical lattices, as rational functions of `z` and `c`.
+//!
+//! Orbits either escape or fall into the attracting fixed point 1, the
+//! magnetized state; both count as leaving, colored by the steps they took.
+//! What is left are the phase transitions.
+
+use crate::complex::{add, div, mul, norm_sq, scale, sub, Complex};
+use crate::formula::Formula;
+
+/// Squared distance from 1 within which an orbit counts as converged.
+pub const CONVERGED_SQ: f64 = 1e-12;
+
+/// Radius beyond which magnet orbits escape. The maps grow like `(z / 2)^2`
+/// or `(z / 3)^2` far out, so the usual radius of 2 would cut off orbits
+/// that still come back.
+pub const ESCAPE_RADIUS: f64 = 100.0;
+
+/// The two magnet maps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Magnet {
+    /// `((z^2 + c - 1) / (2z + c - 2))^2`.
+    One,
+    /// `((z^3 + 3(c - 1)z + (c - 1)(c - 2)) / (3z^2 + 3(c - 2)z + (c - 1)(c - 2) + 1))^2`.
+    Two,
+}
+
+impl Magnet {
+    pub fn step(self, z: Complex, c: Complex) -> Complex {
+        let c1 = sub(c, (1.0, 0.0));
+        let c2 = sub(c, (2.0, 0.0));
+        let ratio = match self {
+            Magnet::One => div(add(mul(z, z), c1), add(scale(z, 2.0), c2)),
+            Magnet::Two => {
+                let z_sq = mul(z, z);
+                let c12 = mul(c1, c2);
+                let numerator = add(add(mul(z_sq, z), scale(mul(c1, z), 3.0)), c12);
+                let denominator = add(
+                    add(scale(z_sq, 3.0), scale(mul(c2, z), 3.0)),
+                    add(c12, (1.0, 0.0)),
+                );
+                div(numerator, denominator)
+            }
+        };
+        mul(ratio, ratio)
+    }
+}
+
+/// Whether `z` has reached the fixed point 1.
+fn at_one(z: Complex) -> bool {
+    norm_sq(sub(z, (1.0, 0.0))) < CONVERGED_SQ
+}
+
+/// Orbits that hit a pole of the map come out as NaN, and count as escaped.
+fn escaped(z: Complex) -> bool {
+    let radius_sq = norm_sq(z);
+    radius_sq.is_nan() || radius_sq > ESCAPE_RADIUS * ESCAPE_RADIUS
+}
+
+/// Smooth count for an orbit that escaped to `z` after `iter` steps. Far
+/// out the maps square `z` up to a factor, so this is the usual smoothing,
+/// measured against [`ESCAPE_RADIUS`] to keep it between `iter - 1` and `iter`.
+fn smooth_iterations(iter: u32, z: Complex) -> f64 {
+    let growth = 0.5 * norm_sq(z).ln() / ESCAPE_RADIUS.ln();
+    iter as f64 - growth.log2()
+}
+
+/// A [`Magnet`] map iterated from 0 with the point as `c`.
+pub struct MagnetMandelbrot {
+    pub magnet: Magnet,
+}
+
+impl Formula for MagnetMandelbrot {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        self.magnet.step(z, c)
+    }
+
+    /// Escapes at [`ESCAPE_RADIUS`]; the view's radius plays no part.
+    fn escaped(&self, z: Complex, _: f64) -> bool {
+        escaped(z)
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations(iter, z)
+    }
+
+    fn converged(&self, z: Complex) -> bool {
+        at_one(z)
+    }
+}
+
+/// The Julia sets of a [`Magnet`] map, iterated from the point.
+pub struct MagnetJulia {
+    pub magnet: Magnet,
+    pub c: Complex,
+}
+
+impl Formula for MagnetJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        self.magnet.step(z, c)
+    }
+
+    fn escaped(&self, z: Complex, _: f64) -> bool {
+        escaped(z)
+    }
+
+    fn smooth_iterations(&self, iter: u32, z: Complex) -> f64 {
+        smooth_iterations(iter, z)
+    }
+
+    fn converged(&self, z: Complex) -> bool {
+        at_one(z)
+    }
+}
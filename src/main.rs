@@ -1,22 +1,12 @@
 use eframe::egui;
-use rayon::prelude::*;
-use std::time::Instant;
-
-// Dynamic rendering - no fixed dimensions
-
-#[derive(Clone, Copy, Debug)]
-struct MandelbrotParams {
-    center_x: f64,
-    center_y: f64,
-    zoom: f64,
-    max_iter: u32,
-    escape_radius: f64,
-    color_offset: f64,
-    color_scale: f64,
-    julia_mode: bool,
-    julia_c_real: f64,
-    julia_c_imag: f64,
-}
+use mandelbrot_rs::polynomial::Polynomial;
+use mandelbrot_rs::renderer::MAX_ZOOM;
+use mandelbrot_rs::worker::{RenderUpdate, RenderWorker};
+use mandelbrot_rs::{
+    Coloring, DensityPlot, FormulaKind, Frame, InteriorColoring, MandelbrotParams, Precision,
+    Renderer, Supersampling,
+};
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug)]
 struct JuliaKeyframe {
@@ -25,26 +15,10 @@ struct JuliaKeyframe {
     c_imag: f64,
 }
 
-impl Default for MandelbrotParams {
-    fn default() -> Self {
-        Self {
-            center_x: -0.75,
-            center_y: 0.0,
-            zoom: 200.0,
-            max_iter: 500,
-            escape_radius: 2.0,
-            color_offset: 0.0,
-            color_scale: 1.0,
-            julia_mode: false,
-            julia_c_real: -0.7,
-            julia_c_imag: 0.27015,
-        }
-    }
-}
-
 struct MandelbrotApp {
     params: MandelbrotParams,
-    buffer: Vec<u32>,
+    renderer: Renderer,
+    worker: RenderWorker,
     texture: Option<egui::TextureHandle>,
     needs_redraw: bool,
     auto_zoom: bool,
@@ -59,10 +33,60 @@ struct MandelbrotApp {
     julia_animation_duration: f64,
     render_width: usize,
     render_height: usize,
+    polynomial_field: ParsedField,
+    expression_field: ParsedField,
+    start_field: ParsedField,
+    sequence_field: ParsedField,
+}
+
+/// A text field whose contents only reach the view parameters once they parse.
+struct ParsedField {
+    text: String,
+    error: Option<String>,
+}
+
+impl ParsedField {
+    fn new(value: &impl ToString) -> Self {
+        Self {
+            text: value.to_string(),
+            error: None,
+        }
+    }
+
+    /// Shows the field after `label`, with the parse error below it if there
+    /// is one. Returns the new value when an edit parses.
+    fn show<T: FromStr<Err = String>>(
+        &mut self,
+        ui: &mut egui::Ui,
+        label: &str,
+        hint: &str,
+    ) -> Option<T> {
+        let mut parsed = None;
+        ui.horizontal(|ui| {
+            ui.label(label);
+            if ui
+                .text_edit_singleline(&mut self.text)
+                .on_hover_text(hint)
+                .changed()
+            {
+                match self.text.parse() {
+                    Ok(value) => {
+                        self.error = None;
+                        parsed = Some(value);
+                    }
+                    Err(error) => self.error = Some(error),
+                }
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::LIGHT_RED, error);
+        }
+        parsed
+    }
 }
 
-impl Default for MandelbrotApp {
-    fn default() -> Self {
+impl MandelbrotApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let julia_keyframes = vec![
             JuliaKeyframe {
                 time: 0.0,
@@ -91,9 +115,18 @@ impl Default for MandelbrotApp {
             },
         ];
 
+        let params = MandelbrotParams::default();
         Self {
-            params: MandelbrotParams::default(),
-            buffer: Vec::new(),
+            polynomial_field: ParsedField::new(&params.polynomial),
+            expression_field: ParsedField::new(&params.expression),
+            start_field: ParsedField::new(&params.start_expression),
+            sequence_field: ParsedField::new(&params.lyapunov_sequence),
+            params,
+            renderer: Renderer::new(800, 600),
+            worker: RenderWorker::spawn({
+                let ctx = cc.egui_ctx.clone();
+                move || ctx.request_repaint()
+            }),
             texture: None,
             needs_redraw: true,
             auto_zoom: false,
@@ -117,15 +150,44 @@ impl eframe::App for MandelbrotApp {
         // Handle keyboard input
         self.handle_keyboard_input(ctx);
 
+        // Show the newest finished pass and any tiles since; the previous
+        // image stays up until then
+        for result in self.worker.drain() {
+            match result.update {
+                RenderUpdate::Preview(frame) => self.show_frame(ctx, &frame),
+                RenderUpdate::Tile(tile) => {
+                    if let Some(texture) = &mut self.texture {
+                        let [width, height] = texture.size();
+                        // Skip tiles left over from a render of another size
+                        if tile.x + tile.width <= width && tile.y + tile.height <= height {
+                            let image = egui::ColorImage::from_rgba_unmultiplied(
+                                [tile.width, tile.height],
+                                &tile.to_rgba(),
+                            );
+                            texture.set_partial(
+                                [tile.x, tile.y],
+                                image,
+                                egui::TextureOptions::NEAREST,
+                            );
+                        }
+                    }
+                }
+                RenderUpdate::Complete(frame) => {
+                    self.show_frame(ctx, &frame);
+                    self.last_render_time = result.elapsed.as_millis() as f64;
+                }
+            }
+        }
+
         // Auto-zoom animation
         if self.auto_zoom {
-            self.params.zoom *= self.zoom_speed;
+            self.params.zoom = (self.params.zoom * self.zoom_speed).min(MAX_ZOOM);
             self.animation_time += 0.016; // ~60fps
             self.needs_redraw = true;
         }
 
         // Julia keyframe animation
-        if self.julia_animation_active && self.params.julia_mode {
+        if self.julia_animation_active && self.params.formula.is_julia() {
             self.julia_animation_time += 0.00016; // ~60fps, 100x slower
             let progress = (self.julia_animation_time / self.julia_animation_duration).min(1.0);
 
@@ -145,28 +207,40 @@ impl eframe::App for MandelbrotApp {
             ui.heading("Mandelbrot Explorer");
 
             // Performance info at the top
-            ui.label(format!("Render time: {:.1}ms", self.last_render_time));
+            ui.horizontal(|ui| {
+                ui.label(format!("Render time: {:.1}ms", self.last_render_time));
+                if self.worker.is_busy() {
+                    ui.spinner();
+                }
+            });
 
             ui.separator();
             ui.label("🎯 View Controls");
 
-            if ui
-                .add(egui::Slider::new(&mut self.params.center_x, -2.0..=1.0).text("Center X"))
-                .changed()
-            {
-                self.needs_redraw = true;
-            }
+            let (mut center_x, mut center_y) = self.params.center_f64();
+            let [x_axis, y_axis] = self.params.formula.axis_names();
+            // The rates of the logistic map keep its orbits bounded up to 4.
+            let (x_range, y_range) = if self.params.formula.is_lyapunov() {
+                (0.0..=4.0, 0.0..=4.0)
+            } else {
+                (-2.0..=1.0, -1.5..=1.5)
+            };
 
-            if ui
-                .add(egui::Slider::new(&mut self.params.center_y, -1.5..=1.5).text("Center Y"))
-                .changed()
-            {
+            let center_x_changed = ui
+                .add(egui::Slider::new(&mut center_x, x_range).text(format!("Center ({x_axis})")))
+                .changed();
+            let center_y_changed = ui
+                .add(egui::Slider::new(&mut center_y, y_range).text(format!("Center ({y_axis})")))
+                .changed();
+
+            if center_x_changed || center_y_changed {
+                self.params.set_center_f64(center_x, center_y);
                 self.needs_redraw = true;
             }
 
             if ui
                 .add(
-                    egui::Slider::new(&mut self.params.zoom, 50.0..=1000000.0)
+                    egui::Slider::new(&mut self.params.zoom, 50.0..=MAX_ZOOM)
                         .logarithmic(true)
                         .text("Zoom"),
                 )
@@ -185,19 +259,92 @@ impl eframe::App for MandelbrotApp {
                 self.needs_redraw = true;
             }
 
-            if ui
-                .add(
-                    egui::Slider::new(&mut self.params.escape_radius, 1.5..=10.0)
-                        .text("Escape Radius"),
-                )
-                .changed()
+            if self.params.formula.uses_escape_radius()
+                && ui
+                    .add(
+                        egui::Slider::new(&mut self.params.escape_radius, 1.5..=10.0)
+                            .text("Escape Radius"),
+                    )
+                    .changed()
+            {
+                self.needs_redraw = true;
+            }
+
+            if self.params.can_subdivide()
+                && ui
+                    .checkbox(&mut self.params.mariani_silver, "Mariani–Silver subdivision")
+                    .on_hover_text(
+                        "Fill rectangles whose border has a single value without iterating them; \
+                         off while an interior coloring is selected",
+                    )
+                    .changed()
             {
                 self.needs_redraw = true;
             }
 
+            let previous_supersampling = self.params.supersampling;
+            egui::ComboBox::from_label("Anti-aliasing")
+                .selected_text(self.params.supersampling.label())
+                .show_ui(ui, |ui| {
+                    for supersampling in Supersampling::ALL {
+                        ui.selectable_value(
+                            &mut self.params.supersampling,
+                            supersampling,
+                            supersampling.label(),
+                        );
+                    }
+                });
+            if self.params.supersampling != previous_supersampling {
+                self.needs_redraw = true;
+            }
+
+            let previous_precision = self.params.precision;
+            egui::ComboBox::from_label("Precision")
+                .selected_text(self.params.precision.label())
+                .show_ui(ui, |ui| {
+                    for precision in Precision::ALL {
+                        ui.selectable_value(
+                            &mut self.params.precision,
+                            precision,
+                            precision.label(),
+                        );
+                    }
+                });
+            if self.params.precision != previous_precision {
+                self.needs_redraw = true;
+            }
+            ui.label(format!(
+                "Using: {}",
+                self.renderer.precision(&self.params).label()
+            ));
+
             ui.separator();
             ui.label("🎨 Colors");
 
+            let previous_coloring = self.params.coloring;
+            egui::ComboBox::from_label("Coloring")
+                .selected_text(self.params.coloring.label())
+                .show_ui(ui, |ui| {
+                    for coloring in Coloring::ALL {
+                        ui.selectable_value(&mut self.params.coloring, coloring, coloring.label());
+                    }
+                });
+            if self.params.coloring != previous_coloring {
+                self.needs_redraw = true;
+            }
+
+            let previous_interior = self.params.interior;
+            egui::ComboBox::from_label("Interior")
+                .selected_text(self.params.interior.label())
+                .show_ui(ui, |ui| {
+                    for interior in InteriorColoring::ALL {
+                        ui.selectable_value(&mut self.params.interior, interior, interior.label());
+                    }
+                });
+            if self.params.interior != previous_interior {
+                self.needs_redraw = true;
+            }
+
             if ui
                 .add(
                     egui::Slider::new(&mut self.params.color_offset, 0.0..=1.0)
@@ -216,16 +363,152 @@ impl eframe::App for MandelbrotApp {
             }
 
             ui.separator();
-            ui.label("🔄 Julia Set Mode");
+            ui.label("🔄 Formula");
+
+            let previous_formula = self.params.formula;
+            egui::ComboBox::from_label("Formula")
+                .selected_text(self.params.formula.label())
+                .show_ui(ui, |ui| {
+                    for formula in FormulaKind::ALL {
+                        ui.selectable_value(&mut self.params.formula, formula, formula.label());
+                    }
+                });
+            if self.params.formula != previous_formula {
+                // Switching between the complex plane and the rate plane
+                // would otherwise leave the view on nothing.
+                if self.params.formula.is_lyapunov() != previous_formula.is_lyapunov() {
+                    self.params.reset_view();
+                }
+                self.needs_redraw = true;
+            }
 
             if ui
-                .checkbox(&mut self.params.julia_mode, "Enable Julia Set")
+                .checkbox(&mut self.params.flip_imaginary, format!("{y_axis} axis up"))
+                .on_hover_text(if self.params.formula.is_lyapunov() {
+                    "Rows grow b downward by default"
+                } else {
+                    "Rows grow the imaginary part downward by default, the usual orientation for the Burning Ship"
+                })
                 .changed()
             {
                 self.needs_redraw = true;
             }
 
-            if self.params.julia_mode {
+            if self.params.formula.uses_power()
+                && ui
+                    .add(egui::Slider::new(&mut self.params.power, -8.0..=8.0).text("Power d"))
+                    .on_hover_text("Whole powers are exact; other powers use the principal branch")
+                    .changed()
+            {
+                self.needs_redraw = true;
+            }
+
+            if self.params.formula.is_newton() {
+                let polynomial = self.polynomial_field.show::<Polynomial>(
+                    ui,
+                    "f(z) =",
+                    "For example z^3 - 1, or (z - 1)(z + 2i)(z - 3) for given roots",
+                );
+                match polynomial {
+                    Some(polynomial) if polynomial.degree() == 0 => {
+                        self.polynomial_field.error = Some("f(z) needs a z term".to_string());
+                    }
+                    Some(polynomial) => {
+                        self.params.polynomial = polynomial;
+                        self.needs_redraw = true;
+                    }
+                    None => {}
+                }
+
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.params.relaxation, 0.05..=2.0)
+                            .text("Relaxation"),
+                    )
+                    .on_hover_text("Damping of each Newton step; 1 is plain Newton's method")
+                    .changed()
+                {
+                    self.needs_redraw = true;
+                }
+            }
+
+            if self.params.formula.is_lyapunov() {
+                if let Some(sequence) = self.sequence_field.show(
+                    ui,
+                    "Sequence",
+                    "Rates of successive steps, such as AABAB: A takes a, B takes b",
+                ) {
+                    self.params.lyapunov_sequence = sequence;
+                    self.needs_redraw = true;
+                }
+            }
+
+            if self.params.formula.is_custom() {
+                if let Some(expression) = self.expression_field.show(
+                    ui,
+                    "z ←",
+                    "For example z^3 + c*sin(z) or z*z + c + p1/z",
+                ) {
+                    self.params.expression = expression;
+                    self.needs_redraw = true;
+                }
+                if !self.params.formula.is_julia() {
+                    if let Some(start) = self.start_field.show(
+                        ui,
+                        "z₀ =",
+                        "Starting value of z, in terms of c and the parameters",
+                    ) {
+                        self.params.start_expression = start;
+                        self.needs_redraw = true;
+                    }
+                }
+
+                // A slider for every parameter up to the highest one used.
+                let count = self
+                    .params
+                    .expression
+                    .parameter_count()
+                    .max(self.params.start_expression.parameter_count());
+                if self.params.expression_parameters.len() < count {
+                    self.params.expression_parameters.resize(count, 0.0);
+                }
+                for (index, value) in self.params.expression_parameters[..count]
+                    .iter_mut()
+                    .enumerate()
+                {
+                    if ui
+                        .add(egui::Slider::new(value, -2.0..=2.0).text(format!("p{}", index + 1)))
+                        .changed()
+                    {
+                        self.needs_redraw = true;
+                    }
+                }
+            }
+
+            if self.params.formula.is_phoenix() {
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.params.phoenix_p_real, -1.5..=1.5)
+                            .text("Phoenix p (Real)"),
+                    )
+                    .on_hover_text("Weight of the previous point of the orbit")
+                    .changed()
+                {
+                    self.needs_redraw = true;
+                }
+
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.params.phoenix_p_imag, -1.5..=1.5)
+                            .text("Phoenix p (Imaginary)"),
+                    )
+                    .changed()
+                {
+                    self.needs_redraw = true;
+                }
+            }
+
+            if self.params.formula.is_julia() {
                 if ui
                     .add(
                         egui::Slider::new(&mut self.params.julia_c_real, -2.0..=2.0)
@@ -247,6 +530,48 @@ impl eframe::App for MandelbrotApp {
                 }
             }
 
+            ui.separator();
+            ui.label("✨ Density plot");
+
+            let previous_density = self.params.density;
+            egui::ComboBox::from_label("Plot")
+                .selected_text(self.params.density.label())
+                .show_ui(ui, |ui| {
+                    for density in DensityPlot::ALL {
+                        ui.selectable_value(&mut self.params.density, density, density.label());
+                    }
+                });
+            if self.params.density != previous_density {
+                self.needs_redraw = true;
+            }
+
+            if self.params.density != DensityPlot::Off
+                && ui
+                    .add(
+                        egui::Slider::new(&mut self.params.density_samples, 1 << 16..=1 << 28)
+                            .logarithmic(true)
+                            .text("Samples"),
+                    )
+                    .changed()
+            {
+                self.needs_redraw = true;
+            }
+
+            if self.params.density == DensityPlot::Nebulabrot {
+                for (limit, channel) in self.params.nebula_limits.iter_mut().zip(["Red", "Green", "Blue"]) {
+                    if ui
+                        .add(
+                            egui::Slider::new(limit, 10..=20000)
+                                .logarithmic(true)
+                                .text(format!("{channel} limit")),
+                        )
+                        .changed()
+                    {
+                        self.needs_redraw = true;
+                    }
+                }
+            }
+
             ui.separator();
             ui.label("🎬 Animation");
 
@@ -260,7 +585,8 @@ impl eframe::App for MandelbrotApp {
             ui.label("🌀 Julia Keyframe Animation");
 
             ui.horizontal(|ui| {
-                if ui.button("▶️ Play Julia Animation").clicked() && self.params.julia_mode {
+                if ui.button("▶️ Play Julia Animation").clicked() && self.params.formula.is_julia()
+                {
                     self.julia_animation_active = true;
                     self.julia_animation_time = 0.0;
                 }
@@ -285,18 +611,17 @@ impl eframe::App for MandelbrotApp {
                 );
             }
 
-            if !self.params.julia_mode {
+            if !self.params.formula.is_julia() {
                 ui.colored_label(
                     egui::Color32::YELLOW,
-                    "⚠️ Enable Julia Set mode to use animation",
+                    "⚠️ Pick the Julia formula to use animation",
                 );
             }
 
             ui.separator();
 
             if ui.button("📸 Reset View").clicked() {
-                self.params = MandelbrotParams::default();
-                self.needs_redraw = true;
+                self.reset();
             }
 
             ui.separator();
@@ -309,10 +634,11 @@ impl eframe::App for MandelbrotApp {
             ui.label("🖱️ Click: Zoom to point");
 
             ui.separator();
-            ui.label(format!("Zoom: {:.0}x", self.params.zoom / 200.0));
+            ui.label(format!("Zoom: {:.3e}x", self.params.zoom / 200.0));
+            let (center_x, center_y) = self.params.center_f64();
             ui.label(format!(
-                "Center: ({:.4}, {:.4})",
-                self.params.center_x, self.params.center_y
+                "Center ({x_axis}, {y_axis}): ({:.4}, {:.4})",
+                center_x, center_y
             ));
         });
 
@@ -328,8 +654,8 @@ impl eframe::App for MandelbrotApp {
             };
 
             // Calculate optimal render resolution based on display size
-            let new_width = (display_size.x as usize).max(200).min(2000);
-            let new_height = (display_size.y as usize).max(150).min(1500);
+            let new_width = (display_size.x as usize).clamp(200, 2000);
+            let new_height = (display_size.y as usize).clamp(150, 1500);
 
             // Check if we need to resize the buffer
             let size_changed = new_width != self.render_width || new_height != self.render_height;
@@ -338,54 +664,62 @@ impl eframe::App for MandelbrotApp {
                 if size_changed {
                     self.render_width = new_width;
                     self.render_height = new_height;
-                    self.buffer
-                        .resize(self.render_width * self.render_height, 0);
-                }
-
-                let start = Instant::now();
-                self.render_fractal();
-                let elapsed = start.elapsed();
-
-                // Update texture
-                let color_image = egui::ColorImage::from_rgba_unmultiplied(
-                    [self.render_width, self.render_height],
-                    &self.buffer_to_rgba(),
-                );
-
-                if let Some(texture) = &mut self.texture {
-                    texture.set(color_image, egui::TextureOptions::NEAREST);
-                } else {
-                    self.texture = Some(ui.ctx().load_texture(
-                        "mandelbrot",
-                        color_image,
-                        egui::TextureOptions::NEAREST,
-                    ));
+                    self.renderer = Renderer::new(self.render_width, self.render_height);
                 }
 
+                self.worker.submit(self.renderer, self.params.clone());
                 self.needs_redraw = false;
-                self.last_render_time = elapsed.as_millis() as f64;
             }
 
             // Display the fractal
+            let (rect, response) =
+                ui.allocate_exact_size(display_size, egui::Sense::click_and_drag());
             if let Some(texture) = &self.texture {
-                let (rect, response) =
-                    ui.allocate_exact_size(display_size, egui::Sense::click_and_drag());
                 ui.put(rect, egui::Image::new((texture.id(), display_size)));
-
-                // Handle mouse interaction
-                self.handle_mouse_interaction(&response, rect, display_size);
+            } else {
+                ui.put(rect, egui::Spinner::new());
             }
+
+            // Handle mouse interaction
+            self.handle_mouse_interaction(&response, rect, display_size);
         });
 
-        // Request repaint for smooth animation
-        if self.auto_zoom || self.julia_animation_active {
+        // Request repaint for smooth animation, or to submit a render for this frame's input
+        if self.auto_zoom || self.julia_animation_active || self.needs_redraw {
             ctx.request_repaint();
         }
     }
 }
 
 impl MandelbrotApp {
+    fn show_frame(&mut self, ctx: &egui::Context, frame: &Frame) {
+        let color_image =
+            egui::ColorImage::from_rgba_unmultiplied([frame.width, frame.height], &frame.to_rgba());
+
+        if let Some(texture) = &mut self.texture {
+            texture.set(color_image, egui::TextureOptions::NEAREST);
+        } else {
+            self.texture =
+                Some(ctx.load_texture("mandelbrot", color_image, egui::TextureOptions::NEAREST));
+        }
+    }
+
+    /// Goes back to the default view, along with the text shown for it.
+    fn reset(&mut self) {
+        self.params = MandelbrotParams::default();
+        self.polynomial_field = ParsedField::new(&self.params.polynomial);
+        self.expression_field = ParsedField::new(&self.params.expression);
+        self.start_field = ParsedField::new(&self.params.start_expression);
+        self.sequence_field = ParsedField::new(&self.params.lyapunov_sequence);
+        self.needs_redraw = true;
+    }
+
     fn handle_keyboard_input(&mut self, ctx: &egui::Context) {
+        // Keys typed into a text field are not shortcuts.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
         // Q: Increase iterations
         if ctx.input(|i| i.key_pressed(egui::Key::Q)) {
             self.params.max_iter = (self.params.max_iter + 10).min(5000);
@@ -400,15 +734,15 @@ impl MandelbrotApp {
 
         // R: Reset view
         if ctx.input(|i| i.key_pressed(egui::Key::R)) {
-            self.params = MandelbrotParams::default();
-            self.needs_redraw = true;
+            self.reset();
         }
 
         // Space: Print coordinates
         if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            let (center_x, center_y) = self.params.center_to_strings();
             println!(
-                "Current view - Center: ({:.6}, {:.6}), Zoom: {:.2}",
-                self.params.center_x, self.params.center_y, self.params.zoom
+                "Current view - Center: ({}, {}), Zoom: {:e}",
+                center_x, center_y, self.params.zoom
             );
         }
 
@@ -432,25 +766,14 @@ impl MandelbrotApp {
 
             // Only apply drag movement when accumulator is significant enough
             if self.drag_accumulator.length() > 2.0 {
-                let scale_x = (self.render_width as f64 / self.params.zoom) / size.x as f64;
-                let scale_y = (self.render_height as f64 / self.params.zoom) / size.y as f64;
-
-                self.params.center_x -= self.drag_accumulator.x as f64 * scale_x;
-                self.params.center_y -= self.drag_accumulator.y as f64 * scale_y;
-                self.needs_redraw = true;
-                self.drag_accumulator = egui::Vec2::ZERO;
+                self.pan_by_drag(size);
             }
         }
 
         if response.drag_stopped() {
             // Apply any remaining drag movement
             if self.drag_accumulator.length() > 0.1 {
-                let scale_x = (self.render_width as f64 / self.params.zoom) / size.x as f64;
-                let scale_y = (self.render_height as f64 / self.params.zoom) / size.y as f64;
-
-                self.params.center_x -= self.drag_accumulator.x as f64 * scale_x;
-                self.params.center_y -= self.drag_accumulator.y as f64 * scale_y;
-                self.needs_redraw = true;
+                self.pan_by_drag(size);
             }
             self.is_dragging = false;
             self.drag_accumulator = egui::Vec2::ZERO;
@@ -461,7 +784,7 @@ impl MandelbrotApp {
             let scroll_delta = response.ctx.input(|i| i.smooth_scroll_delta.y);
             if scroll_delta != 0.0 {
                 let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 1.0 / 1.1 };
-                self.params.zoom *= zoom_factor;
+                self.params.zoom = (self.params.zoom * zoom_factor).min(MAX_ZOOM);
                 self.needs_redraw = true;
             }
         }
@@ -474,71 +797,37 @@ impl MandelbrotApp {
                 let y_ratio = relative_pos.y / size.y;
 
                 // Convert to complex plane coordinates
-                let new_x = self.params.center_x
-                    + (x_ratio as f64 - 0.5) * (self.render_width as f64 / self.params.zoom);
-                let new_y = self.params.center_y
-                    + (y_ratio as f64 - 0.5) * (self.render_height as f64 / self.params.zoom);
-
-                self.params.center_x = new_x;
-                self.params.center_y = new_y;
-                self.params.zoom *= 2.0;
+                self.params.pan(
+                    (x_ratio as f64 - 0.5) * (self.render_width as f64 / self.params.zoom),
+                    (y_ratio as f64 - 0.5)
+                        * (self.render_height as f64 / self.params.zoom)
+                        * self.params.imaginary_direction(),
+                );
+                self.params.zoom = (self.params.zoom * 2.0).min(MAX_ZOOM);
                 self.needs_redraw = true;
             }
         }
     }
 
-    fn render_fractal(&mut self) {
-        let escape_radius_sq = self.params.escape_radius * self.params.escape_radius;
-
-        let mut params = self.params; // Copy params to avoid borrowing issues
-
-        // Scale iterations with zoom level for better detail at high magnifications
-        let zoom_factor = (params.zoom / 200.0).max(1.0); // Base zoom is 200
-        let scaled_iterations = (params.max_iter as f64 * zoom_factor.log10().max(1.0)) as u32;
-        params.max_iter = scaled_iterations.min(5000); // Cap at 5000 for performance
-        self.buffer
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(i, pixel)| {
-                let x = i % self.render_width;
-                let y = i / self.render_width;
-
-                let real =
-                    params.center_x + (x as f64 - self.render_width as f64 / 2.0) / params.zoom;
-                let imag =
-                    params.center_y + (y as f64 - self.render_height as f64 / 2.0) / params.zoom;
-
-                let iterations = if params.julia_mode {
-                    julia_iterations(
-                        real,
-                        imag,
-                        params.julia_c_real,
-                        params.julia_c_imag,
-                        params.max_iter,
-                        escape_radius_sq,
-                    )
-                } else {
-                    mandelbrot_iterations(real, imag, params.max_iter, escape_radius_sq)
-                };
-
-                *pixel = colorize_pixel(
-                    iterations,
-                    params.max_iter,
-                    params.color_scale,
-                    params.color_offset,
-                );
-            });
-    }
-
-    fn buffer_to_rgba(&self) -> Vec<u8> {
-        let mut rgba = Vec::with_capacity(self.buffer.len() * 4);
-        for &pixel in &self.buffer {
-            rgba.push(((pixel >> 16) & 0xFF) as u8); // R
-            rgba.push(((pixel >> 8) & 0xFF) as u8); // G
-            rgba.push((pixel & 0xFF) as u8); // B
-            rgba.push(255); // A
+    /// Pans by the accumulated drag, snapped to whole render pixels so the
+    /// worker can reuse the previous frame. The fractional rest stays in the
+    /// accumulator for the next drag event.
+    fn pan_by_drag(&mut self, size: egui::Vec2) {
+        let pixels_x = (self.drag_accumulator.x * self.render_width as f32 / size.x).trunc();
+        let pixels_y = (self.drag_accumulator.y * self.render_height as f32 / size.y).trunc();
+        if pixels_x == 0.0 && pixels_y == 0.0 {
+            return;
         }
-        rgba
+
+        self.params.pan(
+            -pixels_x as f64 / self.params.zoom,
+            -pixels_y as f64 / self.params.zoom * self.params.imaginary_direction(),
+        );
+        self.drag_accumulator -= egui::vec2(
+            pixels_x * size.x / self.render_width as f32,
+            pixels_y * size.y / self.render_height as f32,
+        );
+        self.needs_redraw = true;
     }
 
     fn interpolate_julia_keyframes(&self, progress: f64) -> (f64, f64) {
@@ -581,69 +870,6 @@ impl MandelbrotApp {
     }
 }
 
-fn colorize_pixel(iterations: f64, max_iter: u32, color_scale: f64, color_offset: f64) -> u32 {
-    if iterations >= max_iter as f64 {
-        return 0x000000; // Black for points in the set
-    }
-
-    let t = ((iterations / max_iter as f64) * color_scale + color_offset).fract();
-
-    // Enhanced color palette
-    let r = (9.0 * (1.0 - t) * t * t * t * 255.0) as u8;
-    let g = (15.0 * (1.0 - t) * (1.0 - t) * t * t * 255.0) as u8;
-    let b = (8.5 * (1.0 - t) * (1.0 - t) * (1.0 - t) * t * 255.0) as u8;
-
-    ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
-}
-
-fn mandelbrot_iterations(c_real: f64, c_imag: f64, max_iter: u32, escape_radius_sq: f64) -> f64 {
-    let mut zr = 0.0;
-    let mut zi = 0.0;
-    let mut iter = 0;
-
-    while zr * zr + zi * zi <= escape_radius_sq && iter < max_iter {
-        let zr_new = zr * zr - zi * zi + c_real;
-        zi = 2.0 * zr * zi + c_imag;
-        zr = zr_new;
-        iter += 1;
-    }
-
-    if iter >= max_iter {
-        max_iter as f64
-    } else {
-        // Smooth coloring
-        let mag = (zr * zr + zi * zi).sqrt();
-        iter as f64 + 1.0 - (mag.ln() / std::f64::consts::LN_2).ln() / std::f64::consts::LN_2
-    }
-}
-
-fn julia_iterations(
-    z_real: f64,
-    z_imag: f64,
-    c_real: f64,
-    c_imag: f64,
-    max_iter: u32,
-    escape_radius_sq: f64,
-) -> f64 {
-    let mut zr = z_real;
-    let mut zi = z_imag;
-    let mut iter = 0;
-
-    while zr * zr + zi * zi <= escape_radius_sq && iter < max_iter {
-        let zr_new = zr * zr - zi * zi + c_real;
-        zi = 2.0 * zr * zi + c_imag;
-        zr = zr_new;
-        iter += 1;
-    }
-
-    if iter >= max_iter {
-        max_iter as f64
-    } else {
-        let mag = (zr * zr + zi * zi).sqrt();
-        iter as f64 + 1.0 - (mag.ln() / std::f64::consts::LN_2).ln() / std::f64::consts::LN_2
-    }
-}
-
 fn main() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -655,6 +881,6 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Mandelbrot Explorer",
         options,
-        Box::new(|_cc| Ok(Box::new(MandelbrotApp::default()))),
+        Box::new(|cc| Ok(Box::new(MandelbrotApp::new(cc)))),
     )
 }
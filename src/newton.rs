@@ -0,0 +1,153 @@
+//! Newton's method on a polynomial `f`, as fractal formulas.
+//!
+//! Each step is `z - a f(z) / f'(z) + c`, with the relaxation `a` (1 for
+//! plain Newton) and `c` 0 for the Newton fractal itself. Nova fractals add a
+//! nonzero `c` and come in a Mandelbrot and a Julia form like `z^2 + c` does.
+//!
+//! Orbits are followed until their steps get shorter than [`TOLERANCE`]
+//! rather than until they escape, and the iteration count is smoothed by where
+//! the step length crossed the tolerance.
+
+use crate::complex::{add, div, norm_sq, scale, sub, Complex};
+use crate::formula::Formula;
+use crate::fractal::Sample;
+use crate::polynomial::Polynomial;
+
+/// Step length below which an orbit counts as converged.
+pub const TOLERANCE: f64 = 1e-7;
+
+/// One relaxed Newton step for `polynomial`, shifted by `c`.
+fn newton_step(polynomial: &Polynomial, relaxation: f64, z: Complex, c: Complex) -> Complex {
+    let (value, derivative) = polynomial.evaluate(z);
+    add(sub(z, scale(div(value, derivative), relaxation)), c)
+}
+
+/// Iterates `formula` from `point` until it converges. Converged samples
+/// record the nearest of `roots`, if any are given.
+fn converge<F: Formula + ?Sized>(
+    formula: &F,
+    point: Complex,
+    max_iter: u32,
+    roots: &[Complex],
+) -> Sample {
+    let (mut z, c) = formula.initial(point);
+    let log_tolerance = TOLERANCE.ln();
+    // As if the orbit had taken a unit step just before starting.
+    let mut last_log_step = 0.0;
+    for iter in 1..=max_iter {
+        let next = formula.step(z, c);
+        let step_sq = norm_sq(sub(next, z));
+        z = next;
+        if !(z.0.is_finite() && z.1.is_finite()) {
+            break;
+        }
+        let log_step = 0.5 * step_sq.ln();
+        if log_step < log_tolerance {
+            // Where the log of the step length crossed the tolerance, between
+            // the last step and this one.
+            let fraction = (log_tolerance - last_log_step) / (log_step - last_log_step);
+            let root = roots
+                .iter()
+                .map(|&root| norm_sq(sub(z, root)))
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(index, _)| index as u32);
+            return Sample::converged((iter - 1) as f64 + fraction, root);
+        }
+        last_log_step = log_step;
+    }
+    Sample::interior(max_iter, 0)
+}
+
+/// The Newton fractal: Newton's method from the point, colored by the root
+/// it finds.
+pub struct Newton {
+    polynomial: Polynomial,
+    relaxation: f64,
+    roots: Vec<Complex>,
+}
+
+impl Newton {
+    pub fn new(polynomial: Polynomial, relaxation: f64) -> Self {
+        let roots = polynomial.roots();
+        Self {
+            polynomial,
+            relaxation,
+            roots,
+        }
+    }
+
+    /// The roots orbits are matched against, indexed like [`Sample::root`].
+    pub fn roots(&self) -> &[Complex] {
+        &self.roots
+    }
+}
+
+impl Formula for Newton {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, (0.0, 0.0))
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        newton_step(&self.polynomial, self.relaxation, z, c)
+    }
+
+    /// Runs until convergence; the escape radius plays no part.
+    fn iterate(&self, point: Complex, max_iter: u32, _: f64, _: bool) -> Sample {
+        converge(self, point, max_iter, &self.roots)
+    }
+}
+
+/// The Nova fractal with the point as `c`. Orbits start at a root of the
+/// polynomial, a critical point of the unrelaxed Newton step.
+pub struct Nova {
+    polynomial: Polynomial,
+    relaxation: f64,
+    start: Complex,
+}
+
+impl Nova {
+    pub fn new(polynomial: Polynomial, relaxation: f64) -> Self {
+        let start = polynomial.roots().first().copied().unwrap_or((0.0, 0.0));
+        Self {
+            polynomial,
+            relaxation,
+            start,
+        }
+    }
+}
+
+impl Formula for Nova {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (self.start, point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        newton_step(&self.polynomial, self.relaxation, z, c)
+    }
+
+    fn iterate(&self, point: Complex, max_iter: u32, _: f64, _: bool) -> Sample {
+        converge(self, point, max_iter, &[])
+    }
+}
+
+/// The Julia sets of the Nova fractal, iterated from the point.
+pub struct NovaJulia {
+    pub polynomial: Polynomial,
+    pub relaxation: f64,
+    pub c: Complex,
+}
+
+impl Formula for NovaJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        newton_step(&self.polynomial, self.relaxation, z, c)
+    }
+
+    fn iterate(&self, point: Complex, max_iter: u32, _: f64, _: bool) -> Sample {
+        converge(self, point, max_iter, &[])
+    }
+}
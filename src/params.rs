@@ -0,0 +1,452 @@
+use crate::bigfloat::BigFloat;
+use crate::expression::Expression;
+use crate::formula::{Formula, FormulaKind};
+use crate::lyapunov::Sequence;
+use crate::perturbation::precision_for_zoom;
+use crate::polynomial::Polynomial;
+
+/// Largest image dimension the center precision is sized for.
+const MAX_IMAGE_SIZE: usize = 1 << 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MandelbrotParams {
+    /// View center, kept in arbitrary precision so deep-zoom locations survive panning.
+    pub center_x: BigFloat,
+    pub center_y: BigFloat,
+    pub zoom: f64,
+    pub max_iter: u32,
+    pub escape_radius: f64,
+    pub color_offset: f64,
+    pub color_scale: f64,
+    pub formula: FormulaKind,
+    /// Power `d` of the Multibrot formulas, `z^d + c`.
+    pub power: f64,
+    /// Polynomial whose roots the Newton formulas look for.
+    pub polynomial: Polynomial,
+    /// Relaxation `a` of the Newton formulas, which step by `a f(z) / f'(z)`.
+    pub relaxation: f64,
+    /// Iteration of the custom formulas, typed in by the user.
+    pub expression: Expression,
+    /// Starting `z` of the custom formula, as an expression in `c`.
+    pub start_expression: Expression,
+    /// Values of the user parameters `p1`, `p2`, … of the custom formulas.
+    pub expression_parameters: Vec<f64>,
+    /// Which of `a` and `b` each step of the Lyapunov formula takes as its rate.
+    pub lyapunov_sequence: Sequence,
+    /// Memory term `p` of the Phoenix formulas, which add `p` times the
+    /// previous point of the orbit.
+    pub phoenix_p_real: f64,
+    pub phoenix_p_imag: f64,
+    /// Constant `c` of the Julia formulas.
+    pub julia_c_real: f64,
+    pub julia_c_imag: f64,
+    /// Draw the imaginary axis growing upward, the mathematical orientation.
+    /// By default it grows downward like pixel rows, which is how the Burning
+    /// Ship is conventionally drawn.
+    pub flip_imaginary: bool,
+    pub precision: Precision,
+    /// Fill rectangles whose border is uniform instead of iterating their inside.
+    pub mariani_silver: bool,
+    pub supersampling: Supersampling,
+    pub coloring: Coloring,
+    pub interior: InteriorColoring,
+    pub density: DensityPlot,
+    /// Random points traced for a density plot.
+    pub density_samples: u64,
+    /// Iteration limits of the red, green and blue channels of the Nebulabrot.
+    pub nebula_limits: [u32; 3],
+}
+
+/// Arithmetic used for the per-pixel iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// Pick the cheapest of the others that still resolves single pixels.
+    Auto,
+    /// Plain `f64`; breaks into blocks around zoom 1e12.
+    Double,
+    /// Double-double (~106 bits); good to around zoom 1e28.
+    DoubleDouble,
+    /// One high-precision reference orbit plus `f64` deltas; no zoom limit.
+    Perturbation,
+}
+
+/// Largest `zoom * width` (pixels per unit times pixels across) that `f64` resolves.
+const DOUBLE_PIXEL_LIMIT: f64 = 1e15;
+
+/// Largest `zoom * width` that double-double resolves.
+const DOUBLE_DOUBLE_PIXEL_LIMIT: f64 = 1e31;
+
+impl Precision {
+    pub const ALL: [Precision; 4] = [
+        Precision::Auto,
+        Precision::Double,
+        Precision::DoubleDouble,
+        Precision::Perturbation,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Precision::Auto => "Auto",
+            Precision::Double => "f64",
+            Precision::DoubleDouble => "Double-double",
+            Precision::Perturbation => "Perturbation",
+        }
+    }
+
+    /// Resolves [`Precision::Auto`] for a view at `zoom` rendered `width` pixels wide.
+    pub fn resolve(self, zoom: f64, width: usize) -> Precision {
+        if self != Precision::Auto {
+            return self;
+        }
+
+        let pixels = zoom * width as f64;
+        if pixels <= DOUBLE_PIXEL_LIMIT {
+            Precision::Double
+        } else if pixels <= DOUBLE_DOUBLE_PIXEL_LIMIT {
+            Precision::DoubleDouble
+        } else {
+            Precision::Perturbation
+        }
+    }
+}
+
+impl std::str::FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Precision::Auto),
+            "f64" | "double" => Ok(Precision::Double),
+            "dd" | "double-double" => Ok(Precision::DoubleDouble),
+            "perturbation" => Ok(Precision::Perturbation),
+            _ => Err(format!("unknown precision: {s}")),
+        }
+    }
+}
+
+/// Anti-aliasing by averaging several samples per pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Supersampling {
+    /// One sample per pixel.
+    Off,
+    Grid2x2,
+    Grid3x3,
+    Grid4x4,
+    /// A 4x4 grid, but only for pixels whose color differs noticeably from a neighbour's.
+    Adaptive,
+}
+
+impl Supersampling {
+    pub const ALL: [Supersampling; 5] = [
+        Supersampling::Off,
+        Supersampling::Grid2x2,
+        Supersampling::Grid3x3,
+        Supersampling::Grid4x4,
+        Supersampling::Adaptive,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Supersampling::Off => "Off",
+            Supersampling::Grid2x2 => "2x2",
+            Supersampling::Grid3x3 => "3x3",
+            Supersampling::Grid4x4 => "4x4",
+            Supersampling::Adaptive => "Adaptive",
+        }
+    }
+
+    /// Samples per pixel edge for the pixels that get supersampled.
+    pub fn grid_size(self) -> usize {
+        match self {
+            Supersampling::Off => 1,
+            Supersampling::Grid2x2 => 2,
+            Supersampling::Grid3x3 => 3,
+            Supersampling::Grid4x4 | Supersampling::Adaptive => 4,
+        }
+    }
+}
+
+impl std::str::FromStr for Supersampling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" | "1x1" => Ok(Supersampling::Off),
+            "2x2" => Ok(Supersampling::Grid2x2),
+            "3x3" => Ok(Supersampling::Grid3x3),
+            "4x4" => Ok(Supersampling::Grid4x4),
+            "adaptive" => Ok(Supersampling::Adaptive),
+            _ => Err(format!("unknown supersampling mode: {s}")),
+        }
+    }
+}
+
+/// Density plots, which trace the orbits of random points and count how
+/// often they pass through each pixel, instead of coloring pixels by their own orbit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DensityPlot {
+    Off,
+    /// Orbits that escape within the iteration limit, in grey.
+    Buddhabrot,
+    /// Orbits that escape within a separate limit per color channel.
+    Nebulabrot,
+    /// Orbits that stay bounded up to the iteration limit.
+    AntiBuddhabrot,
+}
+
+impl DensityPlot {
+    pub const ALL: [DensityPlot; 4] = [
+        DensityPlot::Off,
+        DensityPlot::Buddhabrot,
+        DensityPlot::Nebulabrot,
+        DensityPlot::AntiBuddhabrot,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DensityPlot::Off => "Off",
+            DensityPlot::Buddhabrot => "Buddhabrot",
+            DensityPlot::Nebulabrot => "Nebulabrot",
+            DensityPlot::AntiBuddhabrot => "Anti-Buddhabrot",
+        }
+    }
+}
+
+impl std::str::FromStr for DensityPlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(DensityPlot::Off),
+            "buddhabrot" => Ok(DensityPlot::Buddhabrot),
+            "nebulabrot" => Ok(DensityPlot::Nebulabrot),
+            "anti-buddhabrot" => Ok(DensityPlot::AntiBuddhabrot),
+            _ => Err(format!("unknown density plot: {s}")),
+        }
+    }
+}
+
+/// How escaped points are colored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coloring {
+    /// Palette indexed by the smooth iteration count.
+    Smooth,
+    /// Light background shading to black at the boundary, by the exterior
+    /// distance estimate. Filaments thinner than a pixel still show as lines.
+    DistanceEstimate,
+}
+
+impl Coloring {
+    pub const ALL: [Coloring; 2] = [Coloring::Smooth, Coloring::DistanceEstimate];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Coloring::Smooth => "Smooth iterations",
+            Coloring::DistanceEstimate => "Distance estimate",
+        }
+    }
+}
+
+impl std::str::FromStr for Coloring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "smooth" => Ok(Coloring::Smooth),
+            "distance" | "de" => Ok(Coloring::DistanceEstimate),
+            _ => Err(format!("unknown coloring: {s}")),
+        }
+    }
+}
+
+/// How points inside the set are colored, from their attracting cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteriorColoring {
+    Black,
+    /// A distinct hue per cycle period, so each hyperbolic component stands out.
+    Period,
+    /// Hue from the angle of the cycle's multiplier, brightness from its
+    /// magnitude: bright at a component's nucleus, dark on its boundary.
+    Multiplier,
+    /// Palette bands by the interior distance estimate.
+    Distance,
+}
+
+impl InteriorColoring {
+    pub const ALL: [InteriorColoring; 4] = [
+        InteriorColoring::Black,
+        InteriorColoring::Period,
+        InteriorColoring::Multiplier,
+        InteriorColoring::Distance,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InteriorColoring::Black => "Black",
+            InteriorColoring::Period => "Period",
+            InteriorColoring::Multiplier => "Multiplier",
+            InteriorColoring::Distance => "Interior distance",
+        }
+    }
+}
+
+impl std::str::FromStr for InteriorColoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "black" => Ok(InteriorColoring::Black),
+            "period" => Ok(InteriorColoring::Period),
+            "multiplier" => Ok(InteriorColoring::Multiplier),
+            "distance" => Ok(InteriorColoring::Distance),
+            _ => Err(format!("unknown interior coloring: {s}")),
+        }
+    }
+}
+
+impl Default for MandelbrotParams {
+    fn default() -> Self {
+        Self {
+            center_x: BigFloat::from_f64(-0.75),
+            center_y: BigFloat::from_f64(0.0),
+            zoom: 200.0,
+            max_iter: 500,
+            escape_radius: 2.0,
+            color_offset: 0.0,
+            color_scale: 1.0,
+            formula: FormulaKind::Mandelbrot,
+            power: 2.0,
+            polynomial: Polynomial::default(),
+            relaxation: 1.0,
+            expression: "z^2 + c".parse().expect("default expression is valid"),
+            start_expression: "0".parse().expect("default start is valid"),
+            expression_parameters: Vec::new(),
+            lyapunov_sequence: Sequence::default(),
+            phoenix_p_real: -0.5,
+            phoenix_p_imag: 0.0,
+            julia_c_real: -0.7,
+            julia_c_imag: 0.27015,
+            flip_imaginary: false,
+            precision: Precision::Auto,
+            mariani_silver: false,
+            supersampling: Supersampling::Off,
+            coloring: Coloring::Smooth,
+            interior: InteriorColoring::Black,
+            density: DensityPlot::Off,
+            density_samples: 1 << 22,
+            nebula_limits: [5000, 500, 50],
+        }
+    }
+}
+
+impl MandelbrotParams {
+    /// Iteration limit actually used for rendering.
+    ///
+    /// Scales `max_iter` with zoom level for better detail at high magnifications.
+    pub fn effective_max_iter(&self) -> u32 {
+        let zoom_factor = (self.zoom / 200.0).max(1.0); // Base zoom is 200
+        let scaled_iterations = (self.max_iter as f64 * zoom_factor.log10().max(1.0)) as u32;
+        scaled_iterations.min(5000) // Cap at 5000 for performance
+    }
+
+    /// Whether `other` yields the same samples as `self`, differing at most in
+    /// the palette and coloring modes, so a frame of one can be recolored into the other.
+    pub fn same_samples(&self, other: &MandelbrotParams) -> bool {
+        let recolored = MandelbrotParams {
+            color_offset: other.color_offset,
+            color_scale: other.color_scale,
+            coloring: other.coloring,
+            interior: other.interior,
+            ..self.clone()
+        };
+        recolored == *other && self.needs_distance() == other.needs_distance()
+    }
+
+    /// Whether the coloring needs distance estimates and interior cycles,
+    /// which cost extra work per pixel.
+    pub fn needs_distance(&self) -> bool {
+        self.coloring == Coloring::DistanceEstimate || self.interior != InteriorColoring::Black
+    }
+
+    /// Whether the formula allows Mariani–Silver subdivision. Multibrot
+    /// powers must be whole and positive to keep `z^d + c` a polynomial.
+    pub fn can_subdivide(&self) -> bool {
+        self.formula.has_filled_interior()
+            && (!self.formula.uses_power() || (self.power >= 1.0 && self.power.fract() == 0.0))
+    }
+
+    /// Whether Mariani–Silver subdivision actually runs. Filled pixels copy
+    /// the border's sample, so interior colorings, which vary from pixel to
+    /// pixel inside the set, need every pixel iterated.
+    pub fn subdivides(&self) -> bool {
+        self.mariani_silver && self.interior == InteriorColoring::Black && self.can_subdivide()
+    }
+
+    /// The selected formula, set up with these parameters.
+    pub fn build_formula(&self) -> Box<dyn Formula> {
+        self.formula.build(self)
+    }
+
+    /// Iteration limits of the red, green and blue channels of a density plot.
+    pub fn density_limits(&self) -> [u32; 3] {
+        match self.density {
+            DensityPlot::Nebulabrot => self.nebula_limits,
+            _ => [self.effective_max_iter(); 3],
+        }
+    }
+
+    /// Mantissa bits kept for the center so single pixels stay addressable at this zoom.
+    pub fn coordinate_precision(&self) -> u32 {
+        precision_for_zoom(self.zoom, MAX_IMAGE_SIZE)
+    }
+
+    /// View center rounded to `f64`, enough for shallow zooms and display.
+    pub fn center_f64(&self) -> (f64, f64) {
+        (self.center_x.to_f64(), self.center_y.to_f64())
+    }
+
+    /// Sign of the imaginary-axis step from one pixel row to the next.
+    pub fn imaginary_direction(&self) -> f64 {
+        if self.flip_imaginary {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Centers the view on the picture of the selected formula at its usual
+    /// scale: the Mandelbrot set, or for the Lyapunov formula the square of
+    /// rates the logistic map stays bounded for.
+    pub fn reset_view(&mut self) {
+        let ((x, y), zoom) = if self.formula.is_lyapunov() {
+            ((3.2, 3.2), 400.0)
+        } else {
+            let default = Self::default();
+            (default.center_f64(), default.zoom)
+        };
+        self.set_center_f64(x, y);
+        self.zoom = zoom;
+    }
+
+    /// Moves the center by `(dx, dy)` in complex-plane units without losing precision.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let precision = self.coordinate_precision();
+        self.center_x = (&self.center_x + &BigFloat::from_f64(dx)).truncate(precision);
+        self.center_y = (&self.center_y + &BigFloat::from_f64(dy)).truncate(precision);
+    }
+
+    /// Sets the center from `f64` coordinates, e.g. from a slider.
+    pub fn set_center_f64(&mut self, x: f64, y: f64) {
+        self.center_x = BigFloat::from_f64(x);
+        self.center_y = BigFloat::from_f64(y);
+    }
+
+    /// Center formatted in decimal with enough digits to locate a single pixel.
+    pub fn center_to_strings(&self) -> (String, String) {
+        let decimals = self.zoom.max(1.0).log10().ceil() as usize + 4;
+        (
+            self.center_x.to_decimal_string(decimals),
+            self.center_y.to_decimal_string(decimals),
+        )
+    }
+}
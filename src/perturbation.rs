@@ -0,0 +1,234 @@
+//! Perturbation-theory deep zoom.
+//!
+//! One reference orbit `Z` is iterated in high precision at the view center;
+//! every pixel then only iterates its small offset `dz` from that orbit in `f64`:
+//!
+//! ```text
+//! dz' = (2Z + dz) dz + dc
+//! ```
+//!
+//! Precision is lost when the full value `Z + dz` gets smaller than `dz`
+//! (a "glitch"). Those pixels are detected on the fly and re-referenced onto
+//! an orbit that starts at the critical point `0`, continuing with `dz = Z + dz`.
+
+use crate::bigfloat::BigFloat;
+use crate::fractal::{distance_estimate, smooth_iterations, Sample};
+
+/// Extra mantissa bits kept beyond what is needed to tell pixels apart.
+const GUARD_BITS: u32 = 64;
+
+/// Reference orbits for one view, plus the iteration of per-pixel deltas.
+pub struct ReferenceOrbit {
+    /// Orbit of the view center, stored in `f64` once computed.
+    center_orbit: Vec<(f64, f64)>,
+    /// Orbit of the critical point `0`, used after a glitch is detected.
+    /// `None` when it is the same as `center_orbit` (Mandelbrot mode).
+    critical_orbit: Option<Vec<(f64, f64)>>,
+    /// Whether the per-pixel offset is applied to `c` (Mandelbrot) or `z0` (Julia).
+    julia: bool,
+}
+
+/// Number of mantissa bits needed to resolve single pixels at `zoom`.
+pub fn precision_for_zoom(zoom: f64, width: usize) -> u32 {
+    let pixels_per_unit = zoom * width.max(1) as f64;
+    pixels_per_unit.log2().max(0.0).ceil() as u32 + GUARD_BITS
+}
+
+impl ReferenceOrbit {
+    /// Reference for the Mandelbrot set: `Z0 = 0`, `C = center`. Returns
+    /// `None` if `cancelled` reported `true` before the orbit was done.
+    pub fn mandelbrot(
+        center_x: &BigFloat,
+        center_y: &BigFloat,
+        max_iter: u32,
+        escape_radius_sq: f64,
+        precision: u32,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<Self> {
+        let center_orbit = compute_orbit(
+            (BigFloat::zero(), BigFloat::zero()),
+            (center_x, center_y),
+            max_iter,
+            escape_radius_sq,
+            precision,
+            cancelled,
+        )?;
+
+        Some(Self {
+            center_orbit,
+            critical_orbit: None,
+            julia: false,
+        })
+    }
+
+    /// Reference for a Julia set: `Z0 = center`, constant `c`. Returns
+    /// `None` if `cancelled` reported `true` before both orbits were done.
+    #[allow(clippy::too_many_arguments)]
+    pub fn julia(
+        center_x: &BigFloat,
+        center_y: &BigFloat,
+        c_real: f64,
+        c_imag: f64,
+        max_iter: u32,
+        escape_radius_sq: f64,
+        precision: u32,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<Self> {
+        let c = (BigFloat::from_f64(c_real), BigFloat::from_f64(c_imag));
+        let center_orbit = compute_orbit(
+            (center_x.clone(), center_y.clone()),
+            (&c.0, &c.1),
+            max_iter,
+            escape_radius_sq,
+            precision,
+            cancelled,
+        )?;
+        let critical_orbit = compute_orbit(
+            (BigFloat::zero(), BigFloat::zero()),
+            (&c.0, &c.1),
+            max_iter,
+            escape_radius_sq,
+            precision,
+            cancelled,
+        )?;
+
+        Some(Self {
+            center_orbit,
+            critical_orbit: Some(critical_orbit),
+            julia: true,
+        })
+    }
+
+    /// Iterates the pixel at offset `(dx, dy)` from the view center.
+    pub fn iterations(&self, dx: f64, dy: f64, max_iter: u32, escape_radius_sq: f64) -> Sample {
+        self.iterate::<false>(dx, dy, max_iter, escape_radius_sq)
+    }
+
+    /// [`ReferenceOrbit::iterations`] that also estimates the distance of escaping points to the set.
+    pub fn distance(&self, dx: f64, dy: f64, max_iter: u32, escape_radius_sq: f64) -> Sample {
+        self.iterate::<true>(dx, dy, max_iter, escape_radius_sq)
+    }
+
+    fn iterate<const DISTANCE: bool>(
+        &self,
+        dx: f64,
+        dy: f64,
+        max_iter: u32,
+        escape_radius_sq: f64,
+    ) -> Sample {
+        let (dc_r, dc_i) = if self.julia { (0.0, 0.0) } else { (dx, dy) };
+        let (mut dzr, mut dzi) = if self.julia { (dx, dy) } else { (0.0, 0.0) };
+        // Derivative of the full orbit with respect to the pixel, kept in plain
+        // `f64`: only its magnitude matters. `dz/dz0` starts at 1, `dz/dc` at 0.
+        let (mut der_r, mut der_i) = if self.julia { (1.0, 0.0) } else { (0.0, 0.0) };
+        let der_increment = if self.julia { 0.0 } else { 1.0 };
+        let escaped = |iter: u32, zr: f64, zi: f64, der_r: f64, der_i: f64| {
+            let iterations = smooth_iterations(iter, zr, zi);
+            if DISTANCE {
+                Sample::escaped_at_distance(iterations, distance_estimate(zr, zi, der_r, der_i))
+            } else {
+                Sample::escaped(iterations)
+            }
+        };
+
+        let mut orbit = &self.center_orbit;
+        let mut m = 0;
+        let mut iter = 0;
+
+        let (zr, zi) = (orbit[0].0 + dzr, orbit[0].1 + dzi);
+        if zr * zr + zi * zi > escape_radius_sq {
+            return escaped(0, zr, zi, der_r, der_i);
+        }
+
+        // No cycle check, as in the double-double kernel: at these zooms
+        // escaping orbits move by less than any fixed tolerance.
+        while iter < max_iter {
+            // Reference ran out (it escaped): continue on the critical orbit.
+            if m + 1 >= orbit.len() {
+                dzr += orbit[m].0;
+                dzi += orbit[m].1;
+                m = 0;
+                orbit = self.critical_orbit.as_ref().unwrap_or(&self.center_orbit);
+            }
+
+            let (zr_ref, zi_ref) = orbit[m];
+
+            if DISTANCE {
+                // der' = 2 (Z + dz) der + increment
+                let (zr, zi) = (zr_ref + dzr, zi_ref + dzi);
+                let der_r_new = 2.0 * (zr * der_r - zi * der_i) + der_increment;
+                der_i = 2.0 * (zr * der_i + zi * der_r);
+                der_r = der_r_new;
+            }
+
+            // dz' = (2Z + dz) dz + dc
+            let tr = 2.0 * zr_ref + dzr;
+            let ti = 2.0 * zi_ref + dzi;
+            let dzr_new = tr * dzr - ti * dzi + dc_r;
+            dzi = tr * dzi + ti * dzr + dc_i;
+            dzr = dzr_new;
+            m += 1;
+            iter += 1;
+
+            let (zr_ref, zi_ref) = orbit[m];
+            let zr = zr_ref + dzr;
+            let zi = zi_ref + dzi;
+            let mag_sq = zr * zr + zi * zi;
+
+            if mag_sq > escape_radius_sq {
+                // Escaping on the last allowed step still counts as inside, as in the `f64` loop.
+                return if iter < max_iter {
+                    escaped(iter, zr, zi, der_r, der_i)
+                } else {
+                    Sample::interior(max_iter, 0)
+                };
+            }
+
+            // Glitch: re-reference onto the critical orbit.
+            if mag_sq < dzr * dzr + dzi * dzi {
+                dzr = zr;
+                dzi = zi;
+                m = 0;
+                orbit = self.critical_orbit.as_ref().unwrap_or(&self.center_orbit);
+            }
+        }
+
+        Sample::interior(max_iter, 0)
+    }
+}
+
+/// Iterates `z^2 + c` in high precision, storing every value as `f64`.
+///
+/// The orbit stops after the first escaped value, or after `max_iter` steps.
+/// Deep views make every step slow, so `cancelled` is polled as it goes.
+fn compute_orbit(
+    z0: (BigFloat, BigFloat),
+    c: (&BigFloat, &BigFloat),
+    max_iter: u32,
+    escape_radius_sq: f64,
+    precision: u32,
+    cancelled: &(dyn Fn() -> bool + Sync),
+) -> Option<Vec<(f64, f64)>> {
+    let (mut zr, mut zi) = z0;
+    let mut orbit = Vec::with_capacity(max_iter as usize + 1);
+
+    for _ in 0..=max_iter {
+        if cancelled() {
+            return None;
+        }
+
+        let point = (zr.to_f64(), zi.to_f64());
+        orbit.push(point);
+        if point.0 * point.0 + point.1 * point.1 > escape_radius_sq {
+            break;
+        }
+
+        let zr_sq = &zr * &zr;
+        let zi_sq = &zi * &zi;
+        let zri = (&zr * &zi).mul_pow2(1);
+        zr = (&(&zr_sq - &zi_sq) + c.0).truncate(precision);
+        zi = (&zri + c.1).truncate(precision);
+    }
+
+    Some(orbit)
+}
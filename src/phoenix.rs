@@ -0,0 +1,51 @@
+//! The Phoenix fractal: `z^2 + c + p z'`, where `z'` is the point of the
+//! orbit before `z`. The memory term `p` stretches the Mandelbrot and Julia
+//! sets into feathered, flame-like shapes.
+
+use crate::complex::{add, mul, Complex};
+use crate::formula::Formula;
+
+/// `z^2 + c + p previous`.
+fn phoenix_step(z: Complex, previous: Complex, c: Complex, p: Complex) -> Complex {
+    add(add(mul(z, z), c), mul(p, previous))
+}
+
+/// Phoenix orbits from 0 with the point as `c`.
+pub struct Phoenix {
+    pub p: Complex,
+}
+
+impl Formula for Phoenix {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    /// The first step, where the point before `z` is 0.
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        phoenix_step(z, (0.0, 0.0), c, self.p)
+    }
+
+    fn step_with_previous(&self, z: Complex, previous: Complex, c: Complex) -> Complex {
+        phoenix_step(z, previous, c, self.p)
+    }
+}
+
+/// The Julia sets of the Phoenix fractal, iterated from the point.
+pub struct PhoenixJulia {
+    pub p: Complex,
+    pub c: Complex,
+}
+
+impl Formula for PhoenixJulia {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        (point, self.c)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        phoenix_step(z, (0.0, 0.0), c, self.p)
+    }
+
+    fn step_with_previous(&self, z: Complex, previous: Complex, c: Complex) -> Complex {
+        phoenix_step(z, previous, c, self.p)
+    }
+}
@@ -0,0 +1,399 @@
+//! Polynomials in `z` with complex coefficients, parsed from text such as
+//! `z^3 - 1` or `(z - 1)(z + 0.5 - 0.866i)(z + 0.5 + 0.866i)`.
+//!
+//! The grammar covers sums, differences and products (written with `*` or by
+//! juxtaposition), whole non-negative powers, parentheses, and numbers with
+//! an optional `i` suffix for imaginary ones.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::complex::{add, div, mul, norm_sq, sub, Complex};
+
+/// Highest degree accepted from text; root finding gets slow and
+/// inaccurate beyond it.
+pub const MAX_DEGREE: usize = 64;
+
+/// Parentheses nested deeper than this are rejected.
+const MAX_NESTING: usize = 64;
+
+/// Durand–Kerner sweeps tried before settling for the roots found so far.
+const ROOT_SWEEPS: u32 = 1000;
+
+/// Roots closer than this are the same multiple root.
+const ROOT_MERGE_DISTANCE: f64 = 1e-6;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial {
+    /// Coefficients by ascending power, without trailing zeros.
+    coefficients: Vec<Complex>,
+}
+
+impl Polynomial {
+    pub fn constant(value: Complex) -> Self {
+        Self::from_coefficients(vec![value])
+    }
+
+    /// The polynomial `z`.
+    pub fn z() -> Self {
+        Self::from_coefficients(vec![(0.0, 0.0), (1.0, 0.0)])
+    }
+
+    /// Polynomial with the given coefficients, by ascending power.
+    pub fn from_coefficients(mut coefficients: Vec<Complex>) -> Self {
+        while coefficients.last() == Some(&(0.0, 0.0)) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// The monic polynomial with exactly these roots.
+    pub fn from_roots(roots: &[Complex]) -> Self {
+        roots
+            .iter()
+            .fold(Self::constant((1.0, 0.0)), |product, &root| {
+                product.mul(&Self::from_coefficients(vec![
+                    (-root.0, -root.1),
+                    (1.0, 0.0),
+                ]))
+            })
+    }
+
+    pub fn coefficients(&self) -> &[Complex] {
+        &self.coefficients
+    }
+
+    /// Degree of the polynomial, 0 for constants including zero.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Value and derivative at `z`, by Horner's rule.
+    pub fn evaluate(&self, z: Complex) -> (Complex, Complex) {
+        self.coefficients.iter().rev().fold(
+            ((0.0, 0.0), (0.0, 0.0)),
+            |(value, derivative), &coefficient| {
+                (
+                    add(mul(value, z), coefficient),
+                    add(mul(derivative, z), value),
+                )
+            },
+        )
+    }
+
+    /// The distinct roots, found numerically with the Durand–Kerner method.
+    /// Multiple roots appear once.
+    pub fn roots(&self) -> Vec<Complex> {
+        let degree = self.degree();
+        if degree == 0 {
+            return Vec::new();
+        }
+        let leading = self.coefficients[degree];
+        let monic = Self::from_coefficients(
+            self.coefficients
+                .iter()
+                .map(|&coefficient| div(coefficient, leading))
+                .collect(),
+        );
+
+        // Powers of a number that is neither real nor a root of unity keep
+        // the starting guesses apart.
+        let mut roots: Vec<Complex> =
+            std::iter::successors(Some((1.0, 0.0)), |&power| Some(mul(power, (0.4, 0.9))))
+                .take(degree)
+                .collect();
+        for _ in 0..ROOT_SWEEPS {
+            let mut largest_step: f64 = 0.0;
+            for k in 0..degree {
+                let denominator = (0..degree)
+                    .filter(|&j| j != k)
+                    .fold((1.0, 0.0), |product, j| {
+                        mul(product, sub(roots[k], roots[j]))
+                    });
+                let step = div(monic.evaluate(roots[k]).0, denominator);
+                if step.0.is_finite() && step.1.is_finite() {
+                    roots[k] = sub(roots[k], step);
+                    largest_step = largest_step.max(norm_sq(step) / norm_sq(roots[k]).max(1.0));
+                }
+            }
+            if largest_step < f64::EPSILON * f64::EPSILON {
+                break;
+            }
+        }
+
+        let mut distinct: Vec<Complex> = Vec::with_capacity(degree);
+        for root in roots {
+            let merge_sq = ROOT_MERGE_DISTANCE * ROOT_MERGE_DISTANCE;
+            if distinct
+                .iter()
+                .all(|&other| norm_sq(sub(root, other)) > merge_sq)
+            {
+                distinct.push(root);
+            }
+        }
+        distinct
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        let coefficient = |p: &Self, i: usize| p.coefficients.get(i).copied().unwrap_or((0.0, 0.0));
+        Self::from_coefficients(
+            (0..length)
+                .map(|i| add(coefficient(self, i), coefficient(other, i)))
+                .collect(),
+        )
+    }
+
+    fn negate(&self) -> Self {
+        Self::from_coefficients(
+            self.coefficients
+                .iter()
+                .map(|&(re, im)| (-re, -im))
+                .collect(),
+        )
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Self::from_coefficients(Vec::new());
+        }
+        let mut product = vec![(0.0, 0.0); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                product[i + j] = add(product[i + j], mul(a, b));
+            }
+        }
+        Self::from_coefficients(product)
+    }
+
+    fn pow(&self, exponent: u32) -> Self {
+        (0..exponent).fold(Self::constant((1.0, 0.0)), |power, _| power.mul(self))
+    }
+}
+
+impl Default for Polynomial {
+    /// `z^3 - 1`, whose Newton fractal is the classic three-way one.
+    fn default() -> Self {
+        Self::from_coefficients(vec![(-1.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 0.0)])
+    }
+}
+
+impl fmt::Display for Polynomial {
+    /// Writes the polynomial by descending power, in a form [`str::parse`] reads back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (power, &(re, im)) in self.coefficients.iter().enumerate().rev() {
+            if (re, im) == (0.0, 0.0) {
+                continue;
+            }
+            let negative = (im == 0.0 && re < 0.0) || (re == 0.0 && im < 0.0);
+            match (first, negative) {
+                (true, true) => f.write_str("-")?,
+                (true, false) => {}
+                (false, true) => f.write_str(" - ")?,
+                (false, false) => f.write_str(" + ")?,
+            }
+            first = false;
+
+            let (re, im) = if negative { (-re, -im) } else { (re, im) };
+            if im == 0.0 {
+                if re != 1.0 || power == 0 {
+                    write!(f, "{re}")?;
+                }
+            } else if re == 0.0 {
+                write!(f, "{im}i")?;
+            } else {
+                write!(
+                    f,
+                    "({re} {} {}i)",
+                    if im < 0.0 { '-' } else { '+' },
+                    im.abs()
+                )?;
+            }
+            match power {
+                0 => {}
+                1 => f.write_str("z")?,
+                _ => write!(f, "z^{power}")?,
+            }
+        }
+        if first {
+            f.write_str("0")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Polynomial {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.char_indices().peekable(),
+            nesting: 0,
+        };
+        let polynomial = parser.sum()?;
+        if let Some((column, c)) = parser.peek() {
+            return Err(format!("unexpected '{c}' at column {}", column + 1));
+        }
+        Ok(polynomial)
+    }
+}
+
+/// Fails if `degree` is above [`MAX_DEGREE`].
+fn check_degree(degree: usize) -> Result<(), String> {
+    if degree > MAX_DEGREE {
+        return Err(format!("degree {degree} is above {MAX_DEGREE}"));
+    }
+    Ok(())
+}
+
+/// Recursive-descent parser over the characters of a polynomial.
+///
+/// Every intermediate result is kept within [`MAX_DEGREE`], so products and
+/// powers stay cheap however the text is written.
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    /// Parentheses currently open.
+    nesting: usize,
+}
+
+impl Parser<'_> {
+    /// Next non-space character and its index, without consuming it.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.peek().is_some_and(|(_, c)| c == expected) && self.chars.next().is_some()
+    }
+
+    /// `product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<Polynomial, String> {
+        let mut sum = self.product()?;
+        loop {
+            if self.eat('+') {
+                sum = sum.add(&self.product()?);
+            } else if self.eat('-') {
+                sum = sum.add(&self.product()?.negate());
+            } else {
+                return Ok(sum);
+            }
+        }
+    }
+
+    /// `signed ('*'? power)*`, where a missing `*` multiplies implicitly.
+    fn product(&mut self) -> Result<Polynomial, String> {
+        let mut product = self.signed()?;
+        loop {
+            let explicit = self.eat('*');
+            let starts_factor = matches!(
+                self.peek(),
+                Some((_, c)) if c == '(' || c == 'z' || c == 'i' || c == '.' || c.is_ascii_digit()
+            );
+            if !explicit && !starts_factor {
+                return Ok(product);
+            }
+            let factor = self.power()?;
+            check_degree(product.degree() + factor.degree())?;
+            product = product.mul(&factor);
+        }
+    }
+
+    /// `'-'* power`; the sign binds looser than `^`, so `-z^2` is `-(z^2)`.
+    /// Signs are counted rather than recursed into, and cancel in pairs.
+    fn signed(&mut self) -> Result<Polynomial, String> {
+        let mut negated = false;
+        while self.eat('-') {
+            negated = !negated;
+        }
+        let power = self.power()?;
+        Ok(if negated { power.negate() } else { power })
+    }
+
+    /// `atom ('^' whole number)?`
+    fn power(&mut self) -> Result<Polynomial, String> {
+        let base = self.atom()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        let (column, exponent) = match self.peek() {
+            Some((column, c)) if c.is_ascii_digit() => (column, self.number()?),
+            Some((column, c)) => {
+                return Err(format!(
+                    "expected a power at column {}, found '{c}'",
+                    column + 1
+                ))
+            }
+            None => return Err("expected a power at the end".to_string()),
+        };
+        if exponent.fract() != 0.0 || exponent > MAX_DEGREE as f64 {
+            return Err(format!(
+                "power at column {} must be a whole number up to {MAX_DEGREE}",
+                column + 1
+            ));
+        }
+        check_degree(base.degree() * exponent as usize)?;
+        Ok(base.pow(exponent as u32))
+    }
+
+    /// A number, optionally imaginary, `i`, `z`, or a parenthesized sum.
+    fn atom(&mut self) -> Result<Polynomial, String> {
+        match self.peek() {
+            Some((_, 'z')) => {
+                self.chars.next();
+                Ok(Polynomial::z())
+            }
+            Some((_, 'i')) => {
+                self.chars.next();
+                Ok(Polynomial::constant((0.0, 1.0)))
+            }
+            Some((column, '(')) => {
+                self.chars.next();
+                if self.nesting == MAX_NESTING {
+                    return Err(format!("nesting too deep at column {}", column + 1));
+                }
+                self.nesting += 1;
+                let inner = self.sum();
+                self.nesting -= 1;
+                let inner = inner?;
+                if !self.eat(')') {
+                    return Err(format!("unclosed '(' at column {}", column + 1));
+                }
+                Ok(inner)
+            }
+            Some((_, c)) if c == '.' || c.is_ascii_digit() => {
+                let value = self.number()?;
+                // `i` right after the digits makes the number imaginary.
+                if self.chars.next_if(|&(_, c)| c == 'i').is_some() {
+                    Ok(Polynomial::constant((0.0, value)))
+                } else {
+                    Ok(Polynomial::constant((value, 0.0)))
+                }
+            }
+            Some((column, c)) => Err(format!("unexpected '{c}' at column {}", column + 1)),
+            None => Err("unexpected end of polynomial".to_string()),
+        }
+    }
+
+    /// Digits with an optional fraction and exponent.
+    fn number(&mut self) -> Result<f64, String> {
+        let mut text = String::new();
+        let start = self.peek().map_or(0, |(column, _)| column);
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| c == '.' || c.is_ascii_digit()) {
+            text.push(c);
+        }
+        if let Some((_, e)) = self.chars.next_if(|&(_, c)| c == 'e' || c == 'E') {
+            text.push(e);
+            if let Some((_, sign)) = self.chars.next_if(|&(_, c)| c == '+' || c == '-') {
+                text.push(sign);
+            }
+            while let Some((_, c)) = self.chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+                text.push(c);
+            }
+        }
+        text.parse()
+            .map_err(|_| format!("invalid number '{text}' at column {}", start + 1))
+    }
+}
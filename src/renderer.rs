@@ -0,0 +1,1012 @@
+use rayon::prelude::*;
+
+use crate::bigfloat::BigFloat;
+use crate::color::{
+    average_colors, color_difference, colorize_density, colorize_distance, colorize_interior,
+    colorize_lyapunov, colorize_pixel, colorize_root, pixels_to_rgba,
+};
+use crate::density::{Accumulator, CHUNK_SAMPLES};
+use crate::double_double::DoubleDouble;
+use crate::formula::Formula;
+use crate::fractal::{
+    in_main_cardioid_or_bulb, julia_distance_dd, julia_iterations_dd, mandelbrot_distance_dd,
+    mandelbrot_iterations_dd, Sample,
+};
+use crate::params::{Coloring, DensityPlot, MandelbrotParams, Precision, Supersampling};
+use crate::perturbation::{precision_for_zoom, ReferenceOrbit};
+use crate::simd::{julia_iterations_x4, mandelbrot_iterations_x4, LANES};
+
+/// Sampling steps of the progressive passes, coarse to fine.
+const PROGRESSIVE_STEPS: [usize; 4] = [8, 4, 2, 1];
+
+/// Chunks of random points in the first batch of a density plot; each later
+/// batch is twice as large.
+const FIRST_DENSITY_BATCH: u64 = 4;
+
+/// Edge length of the square tiles a pass is split into; a multiple of every progressive step.
+const TILE_SIZE: usize = 64;
+
+/// Neighbouring colors with a channel further apart than this get a pixel
+/// supersampled in [`Supersampling::Adaptive`] mode.
+const ADAPTIVE_THRESHOLD: u32 = 24;
+
+/// Deepest supported zoom; beyond it per-pixel `f64` deltas underflow.
+pub const MAX_ZOOM: f64 = 1e290;
+
+/// Renders fractal images of a fixed size.
+#[derive(Clone, Copy, Debug)]
+pub struct Renderer {
+    width: usize,
+    height: usize,
+}
+
+/// The result of a render: per-pixel samples plus the colors derived from them.
+///
+/// The samples are kept so palette changes only need [`Frame::recolor`].
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// Iteration limit the frame was rendered with.
+    pub max_iter: u32,
+    /// Smooth iteration count per pixel, row-major.
+    pub iterations: Vec<f64>,
+    /// Detected cycle period per pixel (0 if none), row-major.
+    pub periods: Vec<u32>,
+    /// Distance estimate per pixel, row-major; see [`Sample::distance`].
+    pub distances: Vec<f64>,
+    /// Multiplier of the attracting cycle per pixel, row-major; see [`Sample::multiplier`].
+    pub multipliers: Vec<(f64, f64)>,
+    /// Root a Newton orbit converged to per pixel, row-major; see [`Sample::root`].
+    pub roots: Vec<Option<u32>>,
+    /// Lyapunov exponent per pixel, row-major; see [`Sample::exponent`].
+    pub exponents: Vec<Option<f64>>,
+    /// Samples of the supersampling grid per pixel, row-major, each grid
+    /// row by row; empty for pixels not supersampled, and `None` unless
+    /// supersampling is on.
+    pub supersamples: Option<Vec<Vec<Sample>>>,
+    /// Orbit hits per pixel and color channel, row-major, for density plots;
+    /// empty otherwise, as are the per-sample fields of a density plot.
+    pub density: Vec<[u32; 3]>,
+    /// Packed `0xRRGGBB` color per pixel, row-major.
+    pub pixels: Vec<u32>,
+}
+
+/// A finished piece of a full-resolution pass, for showing a render while it runs.
+#[derive(Clone, Debug)]
+pub struct Tile {
+    /// Position of the top-left pixel within the frame.
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Packed `0xRRGGBB` color per pixel, row-major.
+    pub pixels: Vec<u32>,
+}
+
+/// A rectangle of pixels within the frame.
+#[derive(Clone, Copy, Debug)]
+struct Region {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Per-view state for iterating single pixels at the chosen precision.
+///
+/// Only plain `f64` goes through [`Formula`]; the other precisions, and the
+/// vectorized loop, are specific to the quadratic formulas.
+enum Kernel {
+    Double {
+        center: (f64, f64),
+        formula: Box<dyn Formula>,
+    },
+    DoubleDouble {
+        center: (DoubleDouble, DoubleDouble),
+    },
+    Perturbation {
+        reference: ReferenceOrbit,
+        /// Approximate center, for the cardioid and bulb test.
+        center: (f64, f64),
+    },
+}
+
+impl Renderer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The precision [`Renderer::render`] uses for `params`, with `Auto` resolved.
+    /// Formulas other than the quadratic ones are always iterated in `f64`.
+    pub fn precision(&self, params: &MandelbrotParams) -> Precision {
+        if !params.formula.is_quadratic() {
+            return Precision::Double;
+        }
+        params
+            .precision
+            .resolve(params.zoom, self.width.max(self.height))
+    }
+
+    pub fn render(&self, params: &MandelbrotParams) -> Frame {
+        self.render_cancellable(params, &|| false)
+            .expect("render without cancellation always completes")
+    }
+
+    /// Like [`Renderer::render`], but polls `cancelled` between rows and
+    /// returns `None` as soon as it reports `true`.
+    pub fn render_cancellable(
+        &self,
+        params: &MandelbrotParams,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<Frame> {
+        self.render_passes(params, &[1], cancelled, &mut |_| {}, None)
+    }
+
+    /// Renders coarse to fine: 1/8, 1/4, 1/2 and then full resolution.
+    ///
+    /// Each pass only computes the pixels earlier passes skipped and hands a
+    /// block-filled preview to `on_pass`; the full-resolution frame is returned.
+    pub fn render_progressive(
+        &self,
+        params: &MandelbrotParams,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        on_pass: &mut dyn FnMut(&Frame),
+    ) -> Option<Frame> {
+        self.render_passes(params, &PROGRESSIVE_STEPS, cancelled, on_pass, None)
+    }
+
+    /// Like [`Renderer::render_progressive`], but also hands every finished
+    /// tile of the full-resolution pass to `on_tile`, from any thread.
+    pub fn render_progressive_tiles(
+        &self,
+        params: &MandelbrotParams,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        on_pass: &mut dyn FnMut(&Frame),
+        on_tile: &(dyn Fn(&Tile) + Sync),
+    ) -> Option<Frame> {
+        self.render_passes(
+            params,
+            &PROGRESSIVE_STEPS,
+            cancelled,
+            on_pass,
+            Some(on_tile),
+        )
+    }
+
+    /// Pixel offset `(dx, dy)` such that pixel `(x, y)` of a `to` render shows
+    /// the same point as pixel `(x + dx, y + dy)` of a `from` render.
+    ///
+    /// Only defined when `to` is `from` translated by whole pixels, with some
+    /// overlap left to reuse.
+    pub fn pan_offset(
+        &self,
+        from: &MandelbrotParams,
+        to: &MandelbrotParams,
+    ) -> Option<(isize, isize)> {
+        let mut translated = from.clone();
+        translated.center_x = to.center_x.clone();
+        translated.center_y = to.center_y.clone();
+        // Orbits cross the whole view, so no part of a density plot carries over.
+        if translated != *to || to.density != DensityPlot::Off {
+            return None;
+        }
+
+        // Panning snaps to whole pixels, so anything else is a different view.
+        let zoom = to.zoom;
+        let whole_pixels = |from: &BigFloat, to: &BigFloat, size: usize| {
+            let pixels = (to - from).to_f64() * zoom;
+            let rounded = pixels.round();
+            ((pixels - rounded).abs() < 1e-3 && rounded.abs() < size as f64)
+                .then_some(rounded as isize)
+        };
+        let offset_x = whole_pixels(&from.center_x, &to.center_x, self.width)?;
+        let offset_y = whole_pixels(&from.center_y, &to.center_y, self.height)?;
+        Some((offset_x, offset_y * to.imaginary_direction() as isize))
+    }
+
+    /// Renders `params` by shifting `previous` by `offset` (see [`Renderer::pan_offset`])
+    /// and computing only the newly exposed strips.
+    pub fn render_panned(
+        &self,
+        params: &MandelbrotParams,
+        previous: &Frame,
+        offset: (isize, isize),
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<Frame> {
+        let escape_radius_sq = params.escape_radius * params.escape_radius;
+        let max_iter = params.effective_max_iter();
+        let kernel = self.kernel(params, max_iter, escape_radius_sq, cancelled)?;
+        let (offset_x, offset_y) = offset;
+
+        let mut samples = vec![Sample::default(); self.width * self.height];
+        samples
+            .par_chunks_mut(self.width)
+            .enumerate()
+            .try_for_each(|(y, row)| {
+                if cancelled() {
+                    return None;
+                }
+
+                let source_y = y as isize + offset_y;
+                let mut exposed = Vec::new();
+                for (x, sample) in row.iter_mut().enumerate() {
+                    let source_x = x as isize + offset_x;
+                    if (0..previous.width as isize).contains(&source_x)
+                        && (0..previous.height as isize).contains(&source_y)
+                    {
+                        let source = source_y as usize * previous.width + source_x as usize;
+                        *sample = previous.sample(source);
+                    } else {
+                        exposed.push(x);
+                    }
+                }
+
+                let exposed: Vec<(usize, usize)> = exposed.into_iter().map(|x| (x, y)).collect();
+                let computed = self.compute_pixels(&kernel, params, &exposed);
+                for (&(x, _), sample) in exposed.iter().zip(computed) {
+                    row[x] = sample;
+                }
+                Some(())
+            })?;
+
+        let mut frame = self.frame_from_samples(&samples, 1, max_iter, params);
+        if let (Some(grids), Some(previous_grids)) =
+            (&mut frame.supersamples, &previous.supersamples)
+        {
+            // Supersamples sit at the same spots relative to their pixel, so
+            // they move along with it.
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let (source_x, source_y) = (x as isize + offset_x, y as isize + offset_y);
+                    if (0..previous.width as isize).contains(&source_x)
+                        && (0..previous.height as isize).contains(&source_y)
+                    {
+                        let source = source_y as usize * previous.width + source_x as usize;
+                        grids[y * self.width + x] = previous_grids[source].clone();
+                    }
+                }
+            }
+            self.supersample(&kernel, params, &mut frame, cancelled)?;
+        }
+        Some(frame)
+    }
+
+    /// Runs one pass per entry of `steps` (descending powers of two, ending in 1).
+    /// A pass with step `s` samples every pixel whose coordinates are multiples of `s`.
+    ///
+    /// Each pass works through square tiles, center first. Tiles of the final
+    /// pass are handed to `on_tile` as soon as they are done.
+    fn render_passes(
+        &self,
+        params: &MandelbrotParams,
+        steps: &[usize],
+        cancelled: &(dyn Fn() -> bool + Sync),
+        on_pass: &mut dyn FnMut(&Frame),
+        on_tile: Option<&(dyn Fn(&Tile) + Sync)>,
+    ) -> Option<Frame> {
+        if params.density != DensityPlot::Off {
+            // Density plots refine by adding samples rather than pixels.
+            return self.render_density(params, steps.len() > 1, cancelled, on_pass);
+        }
+
+        let escape_radius_sq = params.escape_radius * params.escape_radius;
+        let max_iter = params.effective_max_iter();
+        let kernel = self.kernel(params, max_iter, escape_radius_sq, cancelled)?;
+        let regions = self.tile_regions();
+
+        let mut samples = vec![Sample::default(); self.width * self.height];
+        let mut previous_step = None;
+
+        for &step in steps {
+            // Workers pull tiles in order, so the center fills in first while
+            // idle threads keep taking whatever is left.
+            let tiles: Vec<(Region, Vec<Sample>)> = regions
+                .iter()
+                .par_bridge()
+                .map(|&region| {
+                    if cancelled() {
+                        return None;
+                    }
+
+                    let tile_samples =
+                        self.render_tile(&kernel, params, region, step, previous_step, &samples);
+                    if let Some(on_tile) = on_tile.filter(|_| step == 1) {
+                        on_tile(&Tile::new(region, &tile_samples, max_iter, params));
+                    }
+                    Some((region, tile_samples))
+                })
+                .collect::<Option<_>>()?;
+
+            for (region, tile_samples) in tiles {
+                for (row, tile_row) in tile_samples.chunks(region.width).enumerate() {
+                    let start = (region.y + row) * self.width + region.x;
+                    samples[start..start + region.width].copy_from_slice(tile_row);
+                }
+            }
+
+            let mut frame = self.frame_from_samples(&samples, step, max_iter, params);
+            if step == 1 {
+                self.supersample(&kernel, params, &mut frame, cancelled)?;
+                return Some(frame);
+            }
+            on_pass(&frame);
+            previous_step = Some(step);
+        }
+
+        unreachable!("the last pass has step 1")
+    }
+
+    /// Accumulates the density plot `params` asks for, in batches of growing
+    /// size. With `progressive`, the plot so far is handed to `on_pass` after
+    /// every batch but the last.
+    fn render_density(
+        &self,
+        params: &MandelbrotParams,
+        progressive: bool,
+        cancelled: &(dyn Fn() -> bool + Sync),
+        on_pass: &mut dyn FnMut(&Frame),
+    ) -> Option<Frame> {
+        let accumulator = Accumulator::new(self.width, self.height);
+        let chunks = params.density_samples.div_ceil(CHUNK_SAMPLES);
+        let mut done = 0;
+        let mut batch = if progressive {
+            FIRST_DENSITY_BATCH
+        } else {
+            chunks
+        };
+        loop {
+            let end = (done + batch).min(chunks);
+            accumulator.trace(params, done..end, cancelled)?;
+            done = end;
+            let frame = self.density_frame(accumulator.snapshot(), params);
+            if done == chunks {
+                return Some(frame);
+            }
+            on_pass(&frame);
+            batch *= 2;
+        }
+    }
+
+    fn density_frame(&self, density: Vec<[u32; 3]>, params: &MandelbrotParams) -> Frame {
+        let mut frame = Frame {
+            width: self.width,
+            height: self.height,
+            max_iter: params.density_limits().into_iter().max().unwrap_or(0),
+            iterations: Vec::new(),
+            periods: Vec::new(),
+            distances: Vec::new(),
+            multipliers: Vec::new(),
+            roots: Vec::new(),
+            exponents: Vec::new(),
+            supersamples: None,
+            density,
+            pixels: Vec::new(),
+        };
+        frame.recolor(params);
+        frame
+    }
+
+    /// Splits the image into [`TILE_SIZE`] squares, sorted by distance from the center.
+    fn tile_regions(&self) -> Vec<Region> {
+        let mut regions: Vec<Region> = (0..self.height)
+            .step_by(TILE_SIZE)
+            .flat_map(|y| {
+                (0..self.width).step_by(TILE_SIZE).map(move |x| Region {
+                    x,
+                    y,
+                    width: TILE_SIZE.min(self.width - x),
+                    height: TILE_SIZE.min(self.height - y),
+                })
+            })
+            .collect();
+
+        let distance = |region: &Region| {
+            let dx = (region.x + region.width / 2) as f64 - self.width as f64 / 2.0;
+            let dy = (region.y + region.height / 2) as f64 - self.height as f64 / 2.0;
+            dx * dx + dy * dy
+        };
+        regions.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        regions
+    }
+
+    /// Computes the step-`step` samples of one tile, taking the ones the
+    /// `previous_step` pass already has from `samples`.
+    fn render_tile(
+        &self,
+        kernel: &Kernel,
+        params: &MandelbrotParams,
+        region: Region,
+        step: usize,
+        previous_step: Option<usize>,
+        samples: &[Sample],
+    ) -> Vec<Sample> {
+        let mut tile = Vec::with_capacity(region.width * region.height);
+        if previous_step.is_some() {
+            for y in region.y..region.y + region.height {
+                let start = y * self.width + region.x;
+                tile.extend_from_slice(&samples[start..start + region.width]);
+            }
+        } else {
+            tile.resize(region.width * region.height, Sample::default());
+        }
+
+        if step == 1 && params.subdivides() {
+            let known = (0..region.width * region.height)
+                .map(|i| {
+                    let (x, y) = (region.x + i % region.width, region.y + i / region.width);
+                    previous_step.is_some_and(|prev| x % prev == 0 && y % prev == 0)
+                })
+                .collect();
+            let mut subdivision = Subdivision {
+                renderer: self,
+                kernel,
+                params,
+                region,
+                samples: tile,
+                known,
+            };
+            subdivision.fill(0, 0, region.width - 1, region.height - 1);
+            return subdivision.samples;
+        }
+
+        // Tile origins are multiples of every step, so the sampling grid
+        // lines up across tiles.
+        let pixels: Vec<(usize, usize)> = (region.y..region.y + region.height)
+            .step_by(step)
+            .flat_map(|y| {
+                (region.x..region.x + region.width)
+                    .step_by(step)
+                    .map(move |x| (x, y))
+            })
+            // Already computed by the previous, coarser pass
+            .filter(|(x, y)| !previous_step.is_some_and(|prev| x % prev == 0 && y % prev == 0))
+            .collect();
+
+        let computed = self.compute_pixels(kernel, params, &pixels);
+        for ((x, y), sample) in pixels.into_iter().zip(computed) {
+            tile[(y - region.y) * region.width + x - region.x] = sample;
+        }
+        tile
+    }
+
+    /// Iterates the given `(x, y)` pixels.
+    fn compute_pixels(
+        &self,
+        kernel: &Kernel,
+        params: &MandelbrotParams,
+        pixels: &[(usize, usize)],
+    ) -> Vec<Sample> {
+        let points: Vec<(f64, f64)> = pixels.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+        self.compute_points(kernel, params, &points)
+    }
+
+    /// Iterates the points at the given fractional pixel coordinates.
+    fn compute_points(
+        &self,
+        kernel: &Kernel,
+        params: &MandelbrotParams,
+        points: &[(f64, f64)],
+    ) -> Vec<Sample> {
+        let escape_radius_sq = params.escape_radius * params.escape_radius;
+        let max_iter = params.effective_max_iter();
+        let offsets: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| {
+                (
+                    (x - self.width as f64 / 2.0) / params.zoom,
+                    (y - self.height as f64 / 2.0) / params.zoom * params.imaginary_direction(),
+                )
+            })
+            .collect();
+        kernel.iterate_points(params, &offsets, max_iter, escape_radius_sq)
+    }
+
+    /// Fills in the supersampling grid of every pixel `params.supersampling`
+    /// asks for that does not have one yet, then recolors the frame.
+    fn supersample(
+        &self,
+        kernel: &Kernel,
+        params: &MandelbrotParams,
+        frame: &mut Frame,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<()> {
+        let Some(grids) = &mut frame.supersamples else {
+            return Some(());
+        };
+
+        let grid = params.supersampling.grid_size();
+        // Grid points sit at the centers of a `grid` by `grid` split of the
+        // pixel, so odd grids have one at the pixel's own sample point.
+        let own_point = (grid % 2 == 1).then_some(grid * grid / 2);
+        let pixels = &frame.pixels;
+        let own_sample = |index: usize| Sample {
+            iterations: frame.iterations[index],
+            period: frame.periods[index],
+            distance: frame.distances[index],
+            multiplier: frame.multipliers[index],
+            root: frame.roots[index],
+            exponent: frame.exponents[index],
+        };
+        let needs_supersampling = |x: usize, y: usize| {
+            if params.supersampling != Supersampling::Adaptive {
+                return true;
+            }
+            // Colors rather than samples, so that every formula and coloring
+            // is judged by what ends up on screen.
+            let center = pixels[y * self.width + x];
+            let neighbours = [
+                (x > 0).then(|| pixels[y * self.width + x - 1]),
+                (x + 1 < self.width).then(|| pixels[y * self.width + x + 1]),
+                (y > 0).then(|| pixels[(y - 1) * self.width + x]),
+                (y + 1 < self.height).then(|| pixels[(y + 1) * self.width + x]),
+            ];
+            neighbours
+                .into_iter()
+                .flatten()
+                .any(|neighbour| color_difference(neighbour, center) > ADAPTIVE_THRESHOLD)
+        };
+
+        grids
+            .par_chunks_mut(self.width)
+            .enumerate()
+            .try_for_each(|(y, row)| {
+                if cancelled() {
+                    return None;
+                }
+
+                let columns: Vec<usize> = (0..self.width)
+                    .filter(|&x| row[x].is_empty() && needs_supersampling(x, y))
+                    .collect();
+                let offset = |i: usize| (i as f64 + 0.5) / grid as f64 - 0.5;
+                let computed_points: Vec<usize> =
+                    (0..grid * grid).filter(|&i| Some(i) != own_point).collect();
+                let points: Vec<(f64, f64)> = columns
+                    .iter()
+                    .flat_map(|&x| {
+                        computed_points.iter().map(move |&i| {
+                            (x as f64 + offset(i % grid), y as f64 + offset(i / grid))
+                        })
+                    })
+                    .collect();
+                let computed = self.compute_points(kernel, params, &points);
+
+                for (&x, grid_samples) in columns.iter().zip(computed.chunks(computed_points.len()))
+                {
+                    let mut grid_samples = grid_samples.to_vec();
+                    if let Some(own_point) = own_point {
+                        grid_samples.insert(own_point, own_sample(y * self.width + x));
+                    }
+                    row[x] = grid_samples;
+                }
+                Some(())
+            })?;
+
+        frame.recolor(params);
+        Some(())
+    }
+
+    /// Builds a frame where every pixel shows the sample at the top-left of its `step`-sized block.
+    fn frame_from_samples(
+        &self,
+        samples: &[Sample],
+        step: usize,
+        max_iter: u32,
+        params: &MandelbrotParams,
+    ) -> Frame {
+        let block_samples: Vec<Sample> = if step == 1 {
+            samples.to_vec()
+        } else {
+            (0..self.height)
+                .flat_map(|y| {
+                    let block_row = &samples[(y - y % step) * self.width..][..self.width];
+                    (0..self.width).map(move |x| block_row[x - x % step])
+                })
+                .collect()
+        };
+
+        let mut frame = Frame {
+            width: self.width,
+            height: self.height,
+            max_iter,
+            iterations: block_samples.iter().map(|s| s.iterations).collect(),
+            periods: block_samples.iter().map(|s| s.period).collect(),
+            distances: block_samples.iter().map(|s| s.distance).collect(),
+            multipliers: block_samples.iter().map(|s| s.multiplier).collect(),
+            roots: block_samples.iter().map(|s| s.root).collect(),
+            exponents: block_samples.iter().map(|s| s.exponent).collect(),
+            supersamples: (params.supersampling != Supersampling::Off)
+                .then(|| vec![Vec::new(); self.width * self.height]),
+            density: Vec::new(),
+            pixels: Vec::new(),
+        };
+        frame.recolor(params);
+        frame
+    }
+
+    /// Returns `None` if `cancelled` reported `true` while a perturbation
+    /// reference orbit was computed.
+    fn kernel(
+        &self,
+        params: &MandelbrotParams,
+        max_iter: u32,
+        escape_radius_sq: f64,
+        cancelled: &(dyn Fn() -> bool + Sync),
+    ) -> Option<Kernel> {
+        Some(match self.precision(params) {
+            Precision::Auto | Precision::Double => Kernel::Double {
+                center: params.center_f64(),
+                formula: params.build_formula(),
+            },
+            Precision::DoubleDouble => Kernel::DoubleDouble {
+                center: (
+                    DoubleDouble::from_bigfloat(&params.center_x),
+                    DoubleDouble::from_bigfloat(&params.center_y),
+                ),
+            },
+            Precision::Perturbation => {
+                let precision = precision_for_zoom(params.zoom, self.width.max(self.height));
+                let reference = if params.formula.is_julia() {
+                    ReferenceOrbit::julia(
+                        &params.center_x,
+                        &params.center_y,
+                        params.julia_c_real,
+                        params.julia_c_imag,
+                        max_iter,
+                        escape_radius_sq,
+                        precision,
+                        cancelled,
+                    )
+                } else {
+                    ReferenceOrbit::mandelbrot(
+                        &params.center_x,
+                        &params.center_y,
+                        max_iter,
+                        escape_radius_sq,
+                        precision,
+                        cancelled,
+                    )
+                }?;
+                Kernel::Perturbation {
+                    reference,
+                    center: params.center_f64(),
+                }
+            }
+        })
+    }
+}
+
+/// Mariani–Silver subdivision of one tile: a rectangle whose border samples
+/// all agree is filled with that sample instead of being iterated.
+///
+/// Only points that never escape ever agree exactly, and a border of such
+/// points cannot enclose an escaping one (the set of non-escaping points has
+/// no holes). So apart from filaments thinner than a pixel slipping between
+/// border samples, the iteration counts match iterating every pixel. Filled
+/// pixels take the border's cycle period, which can differ from the one
+/// detection would have found for them within the iteration limit; with an
+/// interior coloring subdivision is skipped (see [`MandelbrotParams::subdivides`]).
+struct Subdivision<'a> {
+    renderer: &'a Renderer,
+    kernel: &'a Kernel,
+    params: &'a MandelbrotParams,
+    region: Region,
+    /// Tile samples, row-major.
+    samples: Vec<Sample>,
+    /// Which entries of `samples` are already computed.
+    known: Vec<bool>,
+}
+
+/// Rectangles narrower or shorter than this are iterated pixel by pixel.
+const MIN_SUBDIVISION: usize = 4;
+
+impl Subdivision<'_> {
+    /// Fills the inclusive tile-local rectangle `(x0, y0)..=(x1, y1)`.
+    fn fill(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+        if x1 - x0 < MIN_SUBDIVISION || y1 - y0 < MIN_SUBDIVISION {
+            self.compute((y0..=y1).flat_map(|y| (x0..=x1).map(move |x| (x, y))));
+            return;
+        }
+
+        self.compute(border(x0, y0, x1, y1));
+
+        // Smooth counts of escaping points never agree exactly, so only a
+        // border touching the set is worth subdividing further.
+        let max_iter = self.params.effective_max_iter() as f64;
+        let touches_set = border(x0, y0, x1, y1)
+            .any(|(x, y)| self.samples[y * self.region.width + x].iterations >= max_iter);
+        if !touches_set {
+            self.compute((y0 + 1..y1).flat_map(|y| (x0 + 1..x1).map(move |x| (x, y))));
+            return;
+        }
+
+        // Samples from earlier passes inside the rectangle must agree as well.
+        let first = self.samples[y0 * self.region.width + x0];
+        let uniform = (y0..=y1).all(|y| {
+            (x0..=x1).all(|x| {
+                let index = y * self.region.width + x;
+                !self.known[index] || self.samples[index].iterations == first.iterations
+            })
+        });
+        if uniform {
+            for y in y0..=y1 {
+                let row = y * self.region.width;
+                self.samples[row + x0..=row + x1].fill(first);
+                self.known[row + x0..=row + x1].fill(true);
+            }
+            return;
+        }
+
+        // The halves share the dividing lines, so each line is iterated once.
+        let (mx, my) = ((x0 + x1) / 2, (y0 + y1) / 2);
+        self.fill(x0, y0, mx, my);
+        self.fill(mx, y0, x1, my);
+        self.fill(x0, my, mx, y1);
+        self.fill(mx, my, x1, y1);
+    }
+
+    /// Iterates the not yet known pixels among the tile-local `pixels`.
+    fn compute(&mut self, pixels: impl Iterator<Item = (usize, usize)>) {
+        let width = self.region.width;
+        let pixels: Vec<(usize, usize)> = pixels
+            .filter(|&(x, y)| !self.known[y * width + x])
+            .map(|(x, y)| (self.region.x + x, self.region.y + y))
+            .collect();
+        if pixels.is_empty() {
+            return;
+        }
+
+        let computed = self
+            .renderer
+            .compute_pixels(self.kernel, self.params, &pixels);
+        for ((x, y), sample) in pixels.into_iter().zip(computed) {
+            let index = (y - self.region.y) * width + x - self.region.x;
+            self.samples[index] = sample;
+            self.known[index] = true;
+        }
+    }
+}
+
+/// Pixels on the edge of the inclusive rectangle `(x0, y0)..=(x1, y1)`, each once.
+fn border(x0: usize, y0: usize, x1: usize, y1: usize) -> impl Iterator<Item = (usize, usize)> {
+    let top_and_bottom = [y0, y1]
+        .into_iter()
+        .flat_map(move |y| (x0..=x1).map(move |x| (x, y)));
+    let sides = (y0 + 1..y1).flat_map(move |y| [(x0, y), (x1, y)]);
+    top_and_bottom.chain(sides)
+}
+
+impl Kernel {
+    /// Iterates the pixels at the given `(dx, dy)` offsets from the view center.
+    ///
+    /// Plain `f64` views go through the four-lane kernel unless distances are
+    /// needed; its results match [`Kernel::iterations`] exactly.
+    fn iterate_points(
+        &self,
+        params: &MandelbrotParams,
+        offsets: &[(f64, f64)],
+        max_iter: u32,
+        escape_radius_sq: f64,
+    ) -> Vec<Sample> {
+        let vectorized = match self {
+            Kernel::Double { center, .. }
+                if params.formula.is_quadratic() && !params.needs_distance() =>
+            {
+                Some(center)
+            }
+            _ => None,
+        };
+        let Some(center) = vectorized else {
+            return offsets
+                .iter()
+                .map(|&(dx, dy)| self.iterations(params, dx, dy, max_iter, escape_radius_sq))
+                .collect();
+        };
+
+        let mut samples = Vec::with_capacity(offsets.len());
+        for chunk in offsets.chunks(LANES) {
+            // Pad a short last chunk by repeating its final point.
+            let lane = |lane: usize| chunk[lane.min(chunk.len() - 1)];
+            let real: [f64; LANES] = std::array::from_fn(|i| center.0 + lane(i).0);
+            let imag: [f64; LANES] = std::array::from_fn(|i| center.1 + lane(i).1);
+            let lanes = if params.formula.is_julia() {
+                julia_iterations_x4(
+                    real,
+                    imag,
+                    params.julia_c_real,
+                    params.julia_c_imag,
+                    max_iter,
+                    escape_radius_sq,
+                )
+            } else {
+                mandelbrot_iterations_x4(real, imag, max_iter, escape_radius_sq)
+            };
+            samples.extend_from_slice(&lanes[..chunk.len()]);
+        }
+        samples
+    }
+
+    /// Iterates the pixel at offset `(dx, dy)` from the view center, with a
+    /// distance estimate if the coloring of `params` uses one.
+    fn iterations(
+        &self,
+        params: &MandelbrotParams,
+        dx: f64,
+        dy: f64,
+        max_iter: u32,
+        escape_radius_sq: f64,
+    ) -> Sample {
+        let distance = params.needs_distance();
+        match self {
+            Kernel::Double { center, formula } => formula.iterate(
+                (center.0 + dx, center.1 + dy),
+                max_iter,
+                escape_radius_sq,
+                distance,
+            ),
+            Kernel::DoubleDouble { center } => {
+                let real = center.0 + DoubleDouble::from_f64(dx);
+                let imag = center.1 + DoubleDouble::from_f64(dy);
+                let julia = if distance {
+                    julia_distance_dd
+                } else {
+                    julia_iterations_dd
+                };
+                let mandelbrot = if distance {
+                    mandelbrot_distance_dd
+                } else {
+                    mandelbrot_iterations_dd
+                };
+                if params.formula.is_julia() {
+                    julia(
+                        real,
+                        imag,
+                        params.julia_c_real,
+                        params.julia_c_imag,
+                        max_iter,
+                        escape_radius_sq,
+                    )
+                } else {
+                    mandelbrot(real, imag, max_iter, escape_radius_sq)
+                }
+            }
+            Kernel::Perturbation { reference, center } => {
+                if !params.formula.is_julia()
+                    && in_main_cardioid_or_bulb(center.0 + dx, center.1 + dy)
+                {
+                    return Sample::interior(max_iter, 0);
+                }
+                if distance {
+                    reference.distance(dx, dy, max_iter, escape_radius_sq)
+                } else {
+                    reference.iterations(dx, dy, max_iter, escape_radius_sq)
+                }
+            }
+        }
+    }
+}
+
+impl Tile {
+    fn new(region: Region, samples: &[Sample], max_iter: u32, params: &MandelbrotParams) -> Self {
+        Self {
+            x: region.x,
+            y: region.y,
+            width: region.width,
+            height: region.height,
+            pixels: samples
+                .iter()
+                .map(|s| colorize(s, max_iter, params))
+                .collect(),
+        }
+    }
+
+    /// Returns the tile as opaque RGBA bytes, row-major.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        pixels_to_rgba(&self.pixels)
+    }
+}
+
+impl Frame {
+    /// Recomputes the pixel colors from the stored iteration counts with the
+    /// palette of `params`, without iterating anything.
+    pub fn recolor(&mut self, params: &MandelbrotParams) {
+        if !self.density.is_empty() {
+            let peaks = self.density.iter().fold([0; 3], |peaks, counts| {
+                std::array::from_fn(|channel| peaks[channel].max(counts[channel]))
+            });
+            self.pixels = self
+                .density
+                .par_iter()
+                .map(|&counts| colorize_density(counts, peaks, params.color_scale))
+                .collect();
+            return;
+        }
+
+        let frame = &*self;
+        let pixels = (0..self.iterations.len())
+            .into_par_iter()
+            .map(|index| {
+                let supersamples = frame
+                    .supersamples
+                    .as_ref()
+                    .map_or(&[][..], |grids| &grids[index]);
+                if supersamples.is_empty() {
+                    colorize(&frame.sample(index), frame.max_iter, params)
+                } else {
+                    let colors: Vec<u32> = supersamples
+                        .iter()
+                        .map(|s| colorize(s, frame.max_iter, params))
+                        .collect();
+                    average_colors(&colors)
+                }
+            })
+            .collect();
+        self.pixels = pixels;
+    }
+
+    /// The sample behind pixel `index`, row-major.
+    pub fn sample(&self, index: usize) -> Sample {
+        Sample {
+            iterations: self.iterations[index],
+            period: self.periods[index],
+            distance: self.distances[index],
+            multiplier: self.multipliers[index],
+            root: self.roots[index],
+            exponent: self.exponents[index],
+        }
+    }
+
+    /// Returns the frame as opaque RGBA bytes, row-major.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        pixels_to_rgba(&self.pixels)
+    }
+}
+
+/// Color of one sample with the palette and colorings of `params`.
+fn colorize(sample: &Sample, max_iter: u32, params: &MandelbrotParams) -> u32 {
+    if let Some(exponent) = sample.exponent {
+        return colorize_lyapunov(exponent, params.color_scale);
+    }
+    if let Some(root) = sample.root {
+        return colorize_root(
+            root,
+            sample.iterations,
+            params.color_scale,
+            params.color_offset,
+        );
+    }
+    // Distance estimates are in plane units; the colorings measure them in pixels.
+    if sample.iterations >= max_iter as f64 {
+        return colorize_interior(
+            params.interior,
+            sample.period,
+            sample.multiplier,
+            sample.distance * params.zoom,
+            params.color_scale,
+            params.color_offset,
+        );
+    }
+    match params.coloring {
+        Coloring::Smooth => colorize_pixel(
+            sample.iterations,
+            max_iter,
+            params.color_scale,
+            params.color_offset,
+        ),
+        Coloring::DistanceEstimate => colorize_distance(
+            sample.iterations,
+            sample.distance * params.zoom,
+            max_iter,
+            params.color_scale,
+            params.color_offset,
+        ),
+    }
+}
@@ -0,0 +1,193 @@
+//! Escape-time iteration for four points at once.
+//!
+//! On x86-64 CPUs with AVX the four orbits share 256-bit registers; lanes that
+//! escape or fall into a cycle are masked out while the rest keep iterating.
+//! Everywhere else the scalar loop is used. Both paths perform the same `f64`
+//! operations in the same order, so their results are identical bit for bit.
+
+use crate::fractal::{julia_iterations, mandelbrot_iterations, Sample};
+
+/// Number of points handled per call.
+pub const LANES: usize = 4;
+
+/// [`mandelbrot_iterations`] for four points.
+pub fn mandelbrot_iterations_x4(
+    c_real: [f64; LANES],
+    c_imag: [f64; LANES],
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> [Sample; LANES] {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        let inside: [bool; LANES] = std::array::from_fn(|lane| {
+            crate::fractal::in_main_cardioid_or_bulb(c_real[lane], c_imag[lane])
+        });
+        if inside.iter().all(|&inside| inside) {
+            return [Sample::interior(max_iter, 0); LANES];
+        }
+        // SAFETY: AVX support was just checked.
+        return unsafe {
+            avx::iterate(
+                [0.0; LANES],
+                [0.0; LANES],
+                c_real,
+                c_imag,
+                inside,
+                max_iter,
+                escape_radius_sq,
+            )
+        };
+    }
+
+    std::array::from_fn(|lane| {
+        mandelbrot_iterations(c_real[lane], c_imag[lane], max_iter, escape_radius_sq)
+    })
+}
+
+/// [`julia_iterations`] for four starting points sharing one constant.
+pub fn julia_iterations_x4(
+    z_real: [f64; LANES],
+    z_imag: [f64; LANES],
+    c_real: f64,
+    c_imag: f64,
+    max_iter: u32,
+    escape_radius_sq: f64,
+) -> [Sample; LANES] {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        // SAFETY: AVX support was just checked.
+        return unsafe {
+            avx::iterate(
+                z_real,
+                z_imag,
+                [c_real; LANES],
+                [c_imag; LANES],
+                [false; LANES],
+                max_iter,
+                escape_radius_sq,
+            )
+        };
+    }
+
+    std::array::from_fn(|lane| {
+        julia_iterations(
+            z_real[lane],
+            z_imag[lane],
+            c_real,
+            c_imag,
+            max_iter,
+            escape_radius_sq,
+        )
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use std::arch::x86_64::*;
+
+    use super::LANES;
+    use crate::fractal::{smooth_iterations, Sample, CYCLE_EPSILON};
+
+    /// The vector counterpart of `fractal::iterate`. Lanes flagged in `done`
+    /// are skipped and reported as interior points without a period.
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn iterate(
+        z_real: [f64; LANES],
+        z_imag: [f64; LANES],
+        c_real: [f64; LANES],
+        c_imag: [f64; LANES],
+        done: [bool; LANES],
+        max_iter: u32,
+        escape_radius_sq: f64,
+    ) -> [Sample; LANES] {
+        let mut results = [Sample::interior(max_iter, 0); LANES];
+        // Smoothing is deferred until after the loop, keeping calls out of it.
+        let mut escaped_at = [None; LANES];
+        let lane_bits = |lane: usize| if done[lane] { 0 } else { -1 };
+        let mut active = _mm256_castsi256_pd(_mm256_set_epi64x(
+            lane_bits(3),
+            lane_bits(2),
+            lane_bits(1),
+            lane_bits(0),
+        ));
+
+        let mut zr = _mm256_loadu_pd(z_real.as_ptr());
+        let mut zi = _mm256_loadu_pd(z_imag.as_ptr());
+        let cr = _mm256_loadu_pd(c_real.as_ptr());
+        let ci = _mm256_loadu_pd(c_imag.as_ptr());
+        let two = _mm256_set1_pd(2.0);
+        let escape = _mm256_set1_pd(escape_radius_sq);
+        let epsilon = _mm256_set1_pd(CYCLE_EPSILON);
+        let sign = _mm256_set1_pd(-0.0);
+
+        let (mut saved_r, mut saved_i) = (zr, zi);
+        let mut steps_since_save = 0;
+        let mut save_interval = 1u32;
+        let mut iter = 0;
+
+        // Lanes still iterating when the limit is hit stay interior, as in the scalar loop.
+        while iter < max_iter {
+            let zr_sq = _mm256_mul_pd(zr, zr);
+            let zi_sq = _mm256_mul_pd(zi, zi);
+            let bounded = _mm256_cmp_pd::<_CMP_LE_OQ>(_mm256_add_pd(zr_sq, zi_sq), escape);
+            let escaped = _mm256_movemask_pd(_mm256_andnot_pd(bounded, active));
+            if escaped != 0 {
+                for lane in lanes(escaped) {
+                    escaped_at[lane] = Some(iter);
+                }
+                active = _mm256_and_pd(active, bounded);
+            }
+            if _mm256_movemask_pd(active) == 0 {
+                break;
+            }
+
+            // Finished lanes keep their final z for the smoothing below.
+            let zr_new = _mm256_add_pd(_mm256_sub_pd(zr_sq, zi_sq), cr);
+            let zi_new = _mm256_add_pd(_mm256_mul_pd(_mm256_mul_pd(two, zr), zi), ci);
+            zr = _mm256_blendv_pd(zr, zr_new, active);
+            zi = _mm256_blendv_pd(zi, zi_new, active);
+            iter += 1;
+
+            // Brent's save schedule only depends on the step count, so all
+            // lanes share it.
+            steps_since_save += 1;
+            let near_r = _mm256_cmp_pd::<_CMP_LT_OQ>(
+                _mm256_andnot_pd(sign, _mm256_sub_pd(zr, saved_r)),
+                epsilon,
+            );
+            let near_i = _mm256_cmp_pd::<_CMP_LT_OQ>(
+                _mm256_andnot_pd(sign, _mm256_sub_pd(zi, saved_i)),
+                epsilon,
+            );
+            let cycled = _mm256_and_pd(active, _mm256_and_pd(near_r, near_i));
+            let cycled_bits = _mm256_movemask_pd(cycled);
+            if cycled_bits != 0 {
+                for lane in lanes(cycled_bits) {
+                    results[lane] = Sample::interior(max_iter, steps_since_save);
+                }
+                active = _mm256_andnot_pd(cycled, active);
+            }
+            if steps_since_save == save_interval {
+                saved_r = zr;
+                saved_i = zi;
+                steps_since_save = 0;
+                save_interval = save_interval.saturating_mul(2);
+            }
+        }
+
+        let (mut r, mut i) = ([0.0; LANES], [0.0; LANES]);
+        _mm256_storeu_pd(r.as_mut_ptr(), zr);
+        _mm256_storeu_pd(i.as_mut_ptr(), zi);
+        for lane in 0..LANES {
+            if let Some(iter) = escaped_at[lane] {
+                results[lane] = Sample::escaped(smooth_iterations(iter, r[lane], i[lane]));
+            }
+        }
+        results
+    }
+
+    /// Indices of the lanes set in a movemask result.
+    fn lanes(bits: i32) -> impl Iterator<Item = usize> {
+        (0..LANES).filter(move |lane| bits & (1 << lane) != 0)
+    }
+}
@@ -0,0 +1,203 @@
+//! Background rendering so a UI thread never waits on the fractal.
+//!
+//! Every submitted job gets a new generation number. Submitting a job makes
+//! all older ones stale: a stale job still queued is skipped, and one being
+//! rendered is cancelled at the next row boundary.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::params::MandelbrotParams;
+use crate::renderer::{Frame, Renderer, Tile};
+
+struct RenderJob {
+    generation: u64,
+    renderer: Renderer,
+    params: MandelbrotParams,
+}
+
+/// Progress reported by the render thread.
+pub struct RenderResult {
+    pub generation: u64,
+    pub update: RenderUpdate,
+    pub elapsed: Duration,
+}
+
+pub enum RenderUpdate {
+    /// A block-filled frame from one of the coarse passes.
+    Preview(Frame),
+    /// A finished tile of the full-resolution pass, to be drawn over the last preview.
+    Tile(Tile),
+    /// The full-resolution frame.
+    Complete(Frame),
+}
+
+/// Owns a render thread and hands it jobs.
+pub struct RenderWorker {
+    jobs: Option<Sender<RenderJob>>,
+    results: Receiver<RenderResult>,
+    generation: Arc<AtomicU64>,
+    completed: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RenderWorker {
+    /// Starts the render thread. `notify` is called whenever a result is ready,
+    /// e.g. to wake up the UI.
+    pub fn spawn(notify: impl Fn() + Send + Sync + 'static) -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<RenderJob>();
+        let (result_sender, results) = mpsc::channel();
+        let generation = Arc::new(AtomicU64::new(0));
+        let completed = Arc::new(AtomicU64::new(0));
+
+        let handle = {
+            let generation = Arc::clone(&generation);
+            let completed = Arc::clone(&completed);
+            thread::Builder::new()
+                .name("render".to_string())
+                .spawn(move || {
+                    // The last complete frame, reused by recolors and pans.
+                    let mut last: Option<(Renderer, MandelbrotParams, Frame)> = None;
+
+                    while let Ok(mut job) = job_receiver.recv() {
+                        // Only the newest queued job matters.
+                        while let Ok(newer) = job_receiver.try_recv() {
+                            job = newer;
+                        }
+
+                        let is_stale = || generation.load(Ordering::Relaxed) != job.generation;
+                        if is_stale() {
+                            continue;
+                        }
+
+                        let start = Instant::now();
+                        let send = |update: RenderUpdate| {
+                            let result = RenderResult {
+                                generation: job.generation,
+                                update,
+                                elapsed: start.elapsed(),
+                            };
+                            let sent = result_sender.send(result).is_ok();
+                            notify();
+                            sent
+                        };
+
+                        let same_size = |renderer: &Renderer| {
+                            renderer.width() == job.renderer.width()
+                                && renderer.height() == job.renderer.height()
+                        };
+
+                        // A palette change needs no iteration at all.
+                        if let Some((renderer, params, frame)) = &mut last {
+                            if same_size(renderer) && params.same_samples(&job.params) {
+                                frame.recolor(&job.params);
+                                *params = job.params.clone();
+                                completed.store(job.generation, Ordering::Relaxed);
+                                if !send(RenderUpdate::Complete(frame.clone())) {
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
+
+                        let pan = last.as_ref().and_then(|(renderer, params, frame)| {
+                            same_size(renderer)
+                                .then(|| job.renderer.pan_offset(params, &job.params))
+                                .flatten()
+                                .map(|offset| (frame, offset))
+                        });
+                        let frame = match pan {
+                            // Most of the view is already known, so skip the previews.
+                            Some((previous, offset)) => {
+                                job.renderer
+                                    .render_panned(&job.params, previous, offset, &is_stale)
+                            }
+                            None => job.renderer.render_progressive_tiles(
+                                &job.params,
+                                &is_stale,
+                                &mut |preview| {
+                                    send(RenderUpdate::Preview(preview.clone()));
+                                },
+                                &|tile| {
+                                    send(RenderUpdate::Tile(tile.clone()));
+                                },
+                            ),
+                        };
+                        let Some(frame) = frame else {
+                            continue;
+                        };
+
+                        completed.store(job.generation, Ordering::Relaxed);
+                        last = Some((job.renderer, job.params.clone(), frame.clone()));
+                        if !send(RenderUpdate::Complete(frame)) {
+                            break;
+                        }
+                    }
+                })
+                .expect("failed to spawn render thread")
+        };
+
+        Self {
+            jobs: Some(job_sender),
+            results,
+            generation,
+            completed,
+            handle: Some(handle),
+        }
+    }
+
+    /// Queues a render of `params`, cancelling any render still in progress.
+    /// Returns the job's generation.
+    pub fn submit(&self, renderer: Renderer, params: MandelbrotParams) -> u64 {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(jobs) = &self.jobs {
+            // The thread only goes away on drop, so sending cannot fail before then.
+            let _ = jobs.send(RenderJob {
+                generation,
+                renderer,
+                params,
+            });
+        }
+        generation
+    }
+
+    /// Updates not yet received, oldest first. Updates of stale jobs, and
+    /// anything a later full frame covers, are dropped.
+    pub fn drain(&self) -> Vec<RenderResult> {
+        let latest = self.generation.load(Ordering::Relaxed);
+        let mut results: Vec<RenderResult> = self
+            .results
+            .try_iter()
+            .filter(|result| result.generation == latest)
+            .collect();
+        let last_frame = results.iter().rposition(|result| {
+            matches!(
+                result.update,
+                RenderUpdate::Preview(_) | RenderUpdate::Complete(_)
+            )
+        });
+        if let Some(index) = last_frame {
+            results.drain(..index);
+        }
+        results
+    }
+
+    /// Whether the latest submitted job has not finished yet.
+    pub fn is_busy(&self) -> bool {
+        self.completed.load(Ordering::Relaxed) != self.generation.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for RenderWorker {
+    fn drop(&mut self) {
+        // Cancel the running job and close the queue so the thread exits.
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.jobs = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use mandelbrot_rs::bigfloat::BigFloat;
+use mandelbrot_rs::{MandelbrotParams, Renderer};
+
+fn parse(text: &str) -> BigFloat {
+    text.parse().unwrap()
+}
+
+#[test]
+fn parsing_reads_signs_fractions_and_exponents() {
+    assert_eq!(parse("-0.5").to_f64(), -0.5);
+    assert_eq!(parse("+42").to_f64(), 42.0);
+    assert_eq!(parse(" .25 ").to_f64(), 0.25);
+    assert_eq!(parse("3.").to_f64(), 3.0);
+    assert_eq!(parse("1E3").to_f64(), 1000.0);
+    assert_eq!(parse("-2.5e-3").to_f64(), -0.0025);
+    assert_eq!(parse("1.5e-20").to_f64(), 1.5e-20);
+    assert_eq!(parse("-0").to_f64(), 0.0);
+
+    for invalid in [
+        "",
+        "-",
+        ".",
+        "e5",
+        "1e",
+        "1.2.3",
+        "--1",
+        "1,5",
+        "0x10",
+        "1e99999",
+        // Exponents at the edge of `i64` are rejected rather than overflowing.
+        "1e-9223372036854775808",
+        "1.5e-9223372036854775808",
+        "1e9223372036854775807",
+    ] {
+        assert!(invalid.parse::<BigFloat>().is_err(), "{invalid:?} parsed");
+    }
+}
+
+#[test]
+fn decimal_strings_round_to_the_nearest_digit() {
+    let decimal = |value: f64, digits: usize| BigFloat::from_f64(value).to_decimal_string(digits);
+    assert_eq!(decimal(0.125, 2), "0.13");
+    assert_eq!(decimal(0.124, 2), "0.12");
+    assert_eq!(decimal(-0.375, 2), "-0.38");
+    assert_eq!(decimal(2.0, 3), "2.000");
+    assert_eq!(decimal(-0.5, 0), "-1");
+    assert_eq!(decimal(7.25, 0), "7");
+    // Values that round to zero lose their sign.
+    assert_eq!(decimal(-0.004, 2), "0.00");
+    assert_eq!(decimal(2f64.powi(70), 1), "1180591620717411303424.0");
+    assert_eq!(parse("1e-3").to_decimal_string(5), "0.00100");
+}
+
+#[test]
+fn decimal_strings_parse_back_to_the_same_digits() {
+    for text in [
+        "-0.7436438870371587",
+        "0.000000000000000000000000000001",
+        "-1.749990000000000000000000000000000000123",
+        "12345678901234567890.5",
+    ] {
+        let digits = text
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        assert_eq!(parse(text).to_decimal_string(digits), text);
+    }
+}
+
+#[test]
+fn deep_pans_survive_formatting_and_parsing() {
+    let renderer = Renderer::new(64, 48);
+    let mut from = MandelbrotParams {
+        zoom: 1e30,
+        ..MandelbrotParams::default()
+    };
+    from.center_x = parse("-1.7499900000000000000000000000000123");
+    from.center_y = parse("0.0000000000000000000000000000000456");
+
+    let mut to = from.clone();
+    to.pan(3.0 / to.zoom, -5.0 / to.zoom);
+    assert_eq!(renderer.pan_offset(&from, &to), Some((3, -5)));
+
+    let (x, y) = to.center_to_strings();
+    let mut reparsed = to.clone();
+    reparsed.center_x = parse(&x);
+    reparsed.center_y = parse(&y);
+    // The printed digits go below a pixel, so the view comes back in place.
+    assert_eq!(renderer.pan_offset(&from, &reparsed), Some((3, -5)));
+    assert_eq!(renderer.pan_offset(&to, &reparsed), Some((0, 0)));
+    assert!(((&reparsed.center_x - &to.center_x).to_f64() * to.zoom).abs() < 1e-3);
+}
@@ -0,0 +1,34 @@
+use mandelbrot_rs::fractal::{in_main_cardioid_or_bulb, mandelbrot_iterations};
+
+// With `u32::MAX` iterations these would take minutes if they were iterated.
+#[test]
+fn cardioid_and_bulb_points_return_max_iter_without_iterating() {
+    for (c_real, c_imag) in [
+        (0.0, 0.0),
+        (-0.5, 0.3),
+        (0.2, 0.1),
+        (-1.0, 0.0),
+        (-1.1, 0.2),
+    ] {
+        assert!(in_main_cardioid_or_bulb(c_real, c_imag));
+        assert_eq!(
+            mandelbrot_iterations(c_real, c_imag, u32::MAX, 4.0).iterations,
+            u32::MAX as f64
+        );
+    }
+}
+
+#[test]
+fn points_outside_cardioid_and_bulb_are_not_rejected() {
+    // Escaping points, plus the period-3 bulb which is in the set but not covered.
+    for (c_real, c_imag) in [
+        (0.3, 0.0),
+        (-0.75, 0.2),
+        (-1.3, 0.0),
+        (0.5, 0.5),
+        (-0.12, 0.75),
+    ] {
+        assert!(!in_main_cardioid_or_bulb(c_real, c_imag));
+    }
+    assert!(mandelbrot_iterations(0.3, 0.0, 500, 4.0).iterations < 500.0);
+}
@@ -0,0 +1,107 @@
+use mandelbrot_rs::{DensityPlot, FormulaKind, MandelbrotParams, Renderer};
+
+fn plot(density: DensityPlot) -> MandelbrotParams {
+    MandelbrotParams {
+        density,
+        density_samples: 1 << 15,
+        zoom: 16.0,
+        max_iter: 200,
+        nebula_limits: [400, 100, 20],
+        ..MandelbrotParams::default()
+    }
+}
+
+fn total_hits(density: &[[u32; 3]]) -> u64 {
+    density.iter().flatten().map(|&hits| hits as u64).sum()
+}
+
+#[test]
+fn density_plots_are_deterministic() {
+    let renderer = Renderer::new(64, 48);
+    let params = plot(DensityPlot::Buddhabrot);
+    let first = renderer.render(&params);
+    assert_eq!(first.density, renderer.render(&params).density);
+    assert_eq!(first.density.len(), 64 * 48);
+    assert!(total_hits(&first.density) > 0);
+}
+
+#[test]
+fn orbits_are_only_traced_inside_the_escape_radius() {
+    let (width, height) = (64, 48);
+    let renderer = Renderer::new(width, height);
+    for density in [DensityPlot::Buddhabrot, DensityPlot::AntiBuddhabrot] {
+        let params = plot(density);
+        let frame = renderer.render(&params);
+        let (center_x, center_y) = params.center_f64();
+        for (index, hits) in frame.density.iter().enumerate() {
+            let x = center_x + ((index % width) as f64 - width as f64 / 2.0) / params.zoom;
+            let y = center_y + ((index / width) as f64 - height as f64 / 2.0) / params.zoom;
+            // Half a pixel of slack for the rounding to pixel centers.
+            let slack = 0.75 / params.zoom;
+            if (x * x + y * y).sqrt() > params.escape_radius + slack {
+                assert_eq!(*hits, [0; 3], "hits at ({x}, {y}) for {density:?}");
+            }
+        }
+        assert!(total_hits(&frame.density) > 0, "no hits for {density:?}");
+    }
+}
+
+#[test]
+fn nebulabrot_channels_with_lower_limits_see_fewer_orbits() {
+    let frame = Renderer::new(64, 48).render(&plot(DensityPlot::Nebulabrot));
+    for [red, green, blue] in &frame.density {
+        assert!(red >= green && green >= blue, "{red} {green} {blue}");
+    }
+    let channel = |k: usize| frame.density.iter().map(|hits| hits[k] as u64).sum::<u64>();
+    assert!(channel(0) > channel(1) && channel(1) > channel(2));
+}
+
+#[test]
+fn density_plots_refine_progressively() {
+    let renderer = Renderer::new(64, 48);
+    let params = MandelbrotParams {
+        density_samples: 1 << 16,
+        ..plot(DensityPlot::Buddhabrot)
+    };
+    let mut previews = Vec::new();
+    let frame = renderer
+        .render_progressive(&params, &|| false, &mut |preview| {
+            previews.push(total_hits(&preview.density));
+        })
+        .unwrap();
+
+    assert!(previews.len() >= 2);
+    assert!(previews.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(previews.last() < Some(&total_hits(&frame.density)));
+    assert_eq!(frame.density, renderer.render(&params).density);
+}
+
+#[test]
+fn density_plots_are_not_reused_when_panning() {
+    let renderer = Renderer::new(64, 48);
+    let from = plot(DensityPlot::Buddhabrot);
+    let mut to = from.clone();
+    to.pan(4.0 / to.zoom, 0.0);
+    assert_eq!(renderer.pan_offset(&from, &to), None);
+
+    let (mut from, mut to) = (from, to);
+    from.density = DensityPlot::Off;
+    to.density = DensityPlot::Off;
+    assert_eq!(renderer.pan_offset(&from, &to), Some((4, 0)));
+}
+
+#[test]
+fn converged_orbits_do_not_count_as_bounded() {
+    let (width, height) = (64, 48);
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::MagnetIJulia,
+        julia_c_real: 0.3,
+        julia_c_imag: -0.8,
+        ..plot(DensityPlot::AntiBuddhabrot)
+    };
+    params.set_center_f64(0.0, 0.0);
+    let frame = Renderer::new(width, height).render(&params);
+    // The fixed point 1 that magnet orbits settle on.
+    let one = height / 2 * width + width / 2 + params.zoom as usize;
+    assert_eq!(frame.density[one], [0; 3]);
+}
@@ -0,0 +1,84 @@
+use mandelbrot_rs::fractal::{julia_distance, mandelbrot_distance};
+use mandelbrot_rs::{Coloring, MandelbrotParams, Precision, Renderer};
+
+// A large escape radius makes the estimate accurate to well within a factor of two.
+const ESCAPE_RADIUS_SQ: f64 = 1e12;
+
+#[test]
+fn julia_estimate_matches_distance_to_unit_circle() {
+    // The Julia set of c = 0 is the unit circle.
+    for r in [1.001, 1.01, 1.1, 1.5] {
+        let sample = julia_distance(r, 0.0, 0.0, 0.0, 1000, ESCAPE_RADIUS_SQ);
+        let ratio = sample.distance / (r - 1.0);
+        assert!((0.5..2.0).contains(&ratio), "r = {r}: ratio {ratio}");
+    }
+}
+
+#[test]
+fn mandelbrot_estimate_tracks_distance_to_antenna_tip() {
+    // Left of -2 the nearest point of the set is the tip of the antenna.
+    for d in [1e-4, 1e-2, 0.1, 0.5] {
+        let sample = mandelbrot_distance(-2.0 - d, 0.0, 1000, ESCAPE_RADIUS_SQ);
+        let ratio = sample.distance / d;
+        assert!((0.5..2.0).contains(&ratio), "d = {d}: ratio {ratio}");
+    }
+}
+
+#[test]
+fn distance_coloring_keeps_iteration_counts() {
+    let renderer = Renderer::new(48, 36);
+    let smooth = renderer.render(&MandelbrotParams::default());
+    let params = MandelbrotParams {
+        coloring: Coloring::DistanceEstimate,
+        ..MandelbrotParams::default()
+    };
+    let distance = renderer.render(&params);
+
+    assert_eq!(distance.iterations, smooth.iterations);
+    assert!(smooth
+        .distances
+        .iter()
+        .all(|d| *d == 0.0 || d.is_infinite()));
+    for (iterations, d) in distance.iterations.iter().zip(&distance.distances) {
+        // Points inside get the interior estimate, or 0 where no cycle was found.
+        if *iterations >= distance.max_iter as f64 {
+            assert!(d.is_finite() && *d >= 0.0);
+        } else {
+            assert!(d.is_finite() && *d > 0.0);
+        }
+    }
+}
+
+#[test]
+fn perturbation_estimate_matches_f64() {
+    let renderer = Renderer::new(32, 24);
+    let mut params = MandelbrotParams {
+        zoom: 4000.0,
+        coloring: Coloring::DistanceEstimate,
+        precision: Precision::Double,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(-0.745, 0.11);
+    let expected = renderer.render(&params);
+    let actual = renderer.render(&MandelbrotParams {
+        precision: Precision::Perturbation,
+        ..params.clone()
+    });
+
+    // Orbits of points hugging the boundary are chaotic, so only compare
+    // escaping pixels at least a tenth of a pixel away from it.
+    let escaped = expected
+        .iterations
+        .iter()
+        .map(|&i| i < expected.max_iter as f64);
+    for ((a, b), escaped) in actual
+        .distances
+        .iter()
+        .zip(&expected.distances)
+        .zip(escaped)
+    {
+        if escaped && *b * params.zoom > 0.1 {
+            assert!((a / b - 1.0).abs() < 1e-3, "{a} vs {b}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use mandelbrot_rs::double_double::DoubleDouble;
+use mandelbrot_rs::fractal::{julia_iterations_dd, mandelbrot_iterations_dd};
+use mandelbrot_rs::{
+    julia_iterations, mandelbrot_iterations, MandelbrotParams, Precision, Renderer, Sample,
+};
+
+const MAX_ITER: u32 = 500;
+
+fn assert_close(actual: Sample, expected: Sample, at: (f64, f64)) {
+    let tolerance = 1e-6 * expected.iterations.max(1.0);
+    assert!(
+        (actual.iterations - expected.iterations).abs() < tolerance,
+        "{actual:?} vs {expected:?} at {at:?}"
+    );
+}
+
+#[test]
+fn arithmetic_keeps_what_f64_rounds_away() {
+    let one = DoubleDouble::from_f64(1.0);
+    let tiny = DoubleDouble::from_f64(1e-20);
+    assert_eq!(((one + tiny) - one).to_f64(), 1e-20);
+    assert_eq!((1.0 + 1e-20) - 1.0, 0.0);
+
+    // (1 + 2^-40)^2 = 1 + 2^-39 + 2^-80, whose last term f64 drops.
+    let x = one + DoubleDouble::from_f64(2f64.powi(-40));
+    let square = x.square() - one - DoubleDouble::from_f64(2f64.powi(-39));
+    assert_eq!(square.to_f64(), 2f64.powi(-80));
+    assert_eq!((x * x - x.square()).to_f64(), 0.0);
+}
+
+#[test]
+fn counts_match_f64_at_shallow_zoom() {
+    let dd = DoubleDouble::from_f64;
+    for j in 0..30 {
+        for i in 0..40 {
+            let (x, y) = (-2.2 + i as f64 * 0.07, -1.2 + j as f64 * 0.08);
+            let expected = mandelbrot_iterations(x, y, MAX_ITER, 4.0);
+            let actual = mandelbrot_iterations_dd(dd(x), dd(y), MAX_ITER, 4.0);
+            assert_close(actual, expected, (x, y));
+
+            let expected = julia_iterations(x, y, -0.8, 0.156, MAX_ITER, 4.0);
+            let actual = julia_iterations_dd(dd(x), dd(y), -0.8, 0.156, MAX_ITER, 4.0);
+            assert_close(actual, expected, (x, y));
+        }
+    }
+}
+
+#[test]
+fn neighbouring_pixels_stay_apart_beyond_f64() {
+    // 64 pixels at this zoom are 6.4e17 per unit, past what f64 resolves:
+    // near -2 its spacing is four pixels wide.
+    let renderer = Renderer::new(64, 4);
+    let mut params = MandelbrotParams {
+        zoom: 1e16,
+        max_iter: MAX_ITER,
+        // Orbits leave the disk of radius 2 on the first step out here.
+        escape_radius: 1000.0,
+        ..MandelbrotParams::default()
+    };
+    // Just left of the tip of the antenna, where every point escapes and
+    // counts keep changing with the distance to the tip.
+    params.set_center_f64(-2.0 - 1e-14, 0.0);
+    assert_eq!(renderer.precision(&params), Precision::DoubleDouble);
+
+    let distinct = |precision: Precision| {
+        let frame = renderer.render(&MandelbrotParams {
+            precision,
+            ..params.clone()
+        });
+        let row = &frame.iterations[..64];
+        assert!(row.iter().all(|&i| i < MAX_ITER as f64));
+        row.windows(2).filter(|pair| pair[0] != pair[1]).count() + 1
+    };
+    assert_eq!(distinct(Precision::DoubleDouble), 64);
+    assert!(distinct(Precision::Double) <= 20);
+}
+
+#[test]
+fn auto_precision_switches_at_each_limit() {
+    let auto = |pixels: f64| Precision::Auto.resolve(pixels / 1000.0, 1000);
+    assert_eq!(auto(1e3), Precision::Double);
+    assert_eq!(auto(1e15), Precision::Double);
+    assert_eq!(auto(2e15), Precision::DoubleDouble);
+    assert_eq!(auto(1e31), Precision::DoubleDouble);
+    assert_eq!(auto(2e31), Precision::Perturbation);
+
+    // The height counts when it is the larger side.
+    assert_eq!(
+        Renderer::new(100, 1000).precision(&MandelbrotParams {
+            zoom: 2e12,
+            ..MandelbrotParams::default()
+        }),
+        Precision::DoubleDouble
+    );
+
+    // Explicit choices are kept at any zoom.
+    for precision in [
+        Precision::Double,
+        Precision::DoubleDouble,
+        Precision::Perturbation,
+    ] {
+        assert_eq!(precision.resolve(1.0, 1000), precision);
+        assert_eq!(precision.resolve(1e40, 1000), precision);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs::File;
+
+use mandelbrot_rs::export::write_png;
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+
+/// Keyword and text of each text chunk of the PNG `params` export to.
+fn exported_chunks(params: &MandelbrotParams, name: &str) -> Vec<(String, String)> {
+    let path = std::env::temp_dir().join(format!("mandelbrot-export-{name}.png"));
+    let frame = Renderer::new(8, 6).render(params);
+    write_png(&frame, params, &path).unwrap();
+    let reader = png::Decoder::new(File::open(&path).unwrap())
+        .read_info()
+        .unwrap();
+    let chunks = reader
+        .info()
+        .uncompressed_latin1_text
+        .iter()
+        .map(|chunk| (chunk.keyword.clone(), chunk.text.clone()))
+        .collect();
+    std::fs::remove_file(&path).unwrap();
+    chunks
+}
+
+fn chunk<'a>(chunks: &'a [(String, String)], keyword: &str) -> Option<&'a str> {
+    chunks
+        .iter()
+        .find(|(found, _)| found == keyword)
+        .map(|(_, text)| text.as_str())
+}
+
+#[test]
+fn every_formula_records_its_name_and_iteration_limit() {
+    let params = MandelbrotParams {
+        max_iter: 321,
+        ..MandelbrotParams::default()
+    };
+    let chunks = exported_chunks(&params, "mandelbrot");
+    assert_eq!(chunk(&chunks, "Formula"), Some("Mandelbrot"));
+    assert_eq!(chunk(&chunks, "Max Iterations"), Some("321"));
+    assert_eq!(chunk(&chunks, "Julia C"), None);
+    assert_eq!(chunk(&chunks, "Power"), None);
+}
+
+#[test]
+fn formula_constants_are_recorded() {
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::MultibrotJulia,
+        power: -2.5,
+        julia_c_real: -0.8,
+        julia_c_imag: 0.156,
+        ..MandelbrotParams::default()
+    };
+    let chunks = exported_chunks(&params, "multibrot-julia");
+    assert_eq!(chunk(&chunks, "Formula"), Some("Multibrot Julia"));
+    assert_eq!(chunk(&chunks, "Power"), Some("-2.5"));
+    assert_eq!(chunk(&chunks, "Julia C"), Some("-0.8, 0.156"));
+
+    params.formula = FormulaKind::Custom;
+    params.expression = "z^2 + p1*c".parse().unwrap();
+    params.start_expression = "p2".parse().unwrap();
+    params.expression_parameters = vec![0.5];
+    let chunks = exported_chunks(&params, "custom");
+    assert_eq!(chunk(&chunks, "Expression"), Some("z^2 + p1*c"));
+    assert_eq!(chunk(&chunks, "Start"), Some("p2"));
+    assert_eq!(chunk(&chunks, "Parameters"), Some("0.5, 0"));
+    assert_eq!(chunk(&chunks, "Julia C"), None);
+
+    params.formula = FormulaKind::PhoenixJulia;
+    params.phoenix_p_real = -0.5;
+    params.phoenix_p_imag = 0.0;
+    let chunks = exported_chunks(&params, "phoenix-julia");
+    assert_eq!(chunk(&chunks, "Phoenix P"), Some("-0.5, 0"));
+    assert_eq!(chunk(&chunks, "Julia C"), Some("-0.8, 0.156"));
+
+    params.formula = FormulaKind::Newton;
+    let chunks = exported_chunks(&params, "newton");
+    assert_eq!(
+        chunk(&chunks, "Polynomial"),
+        Some(params.polynomial.to_string().as_str())
+    );
+    assert_eq!(chunk(&chunks, "Escape Radius"), None);
+}
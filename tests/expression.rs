@@ -0,0 +1,88 @@
+use mandelbrot_rs::complex::{norm_sq, sub};
+use mandelbrot_rs::expression::{Custom, Expression};
+use mandelbrot_rs::formula::Formula;
+use mandelbrot_rs::mandelbrot_iterations;
+
+fn parse(text: &str) -> Expression {
+    text.parse().unwrap()
+}
+
+fn custom(iteration: &str, start: &str, parameters: &[f64]) -> Custom {
+    Custom {
+        iteration: parse(iteration),
+        start: parse(start),
+        parameters: parameters.to_vec(),
+    }
+}
+
+#[test]
+fn default_expression_matches_the_mandelbrot_set() {
+    let formula = custom("z^2 + c", "0", &[]);
+    // Only points outside the cardioid, which the built-in formula skips.
+    for (x, y) in [(0.3, 0.0), (-1.3, 0.05), (-0.12, 0.75), (-0.745, 0.11)] {
+        assert_eq!(
+            formula.iterate((x, y), 500, 4.0, false),
+            mandelbrot_iterations(x, y, 500, 4.0)
+        );
+    }
+}
+
+#[test]
+fn expressions_evaluate_functions_and_parameters() {
+    let z = (0.3, -1.2);
+    let c = (-0.5, 0.25);
+    let close = |text: &str, parameters: &[f64], expected| {
+        let value = parse(text).evaluate(z, c, parameters);
+        assert!(norm_sq(sub(value, expected)) < 1e-24, "{text} = {value:?}");
+    };
+    close("sin(z)^2 + cos(z)^2", &[], (1.0, 0.0));
+    close("exp(log(z))", &[], z);
+    close("tanh(z) - sinh(z)/cosh(z)", &[], (0.0, 0.0));
+    close("conj(z) z", &[], (norm_sq(z), 0.0));
+    close("2z + 3i - c", &[], (1.1, 0.35));
+    close("re(c) + im(c)i + abs(3 + 4i)", &[], (4.5, 0.25));
+    close("z^-1 * z", &[], (1.0, 0.0));
+    close("p1 * z + p2", &[2.0], (0.6, -2.4));
+    close("e^(i pi)", &[], (-1.0, 0.0));
+    close("(z + 1)(z - 1) - (z^2 - 1)", &[], (0.0, 0.0));
+}
+
+#[test]
+fn constant_parts_are_folded() {
+    assert_eq!(parse("z^(1 + 1) + c"), parse("z^2+c"));
+    assert_eq!(parse("z * (2 * 3)"), parse("z * 6"));
+    assert_ne!(parse("z * z + c"), parse("z^2 + c"));
+    assert_eq!(parse("z*z + c + p3/z").parameter_count(), 3);
+    assert_eq!(parse("z^2 + c").parameter_count(), 0);
+}
+
+#[test]
+fn parse_errors_point_at_the_problem() {
+    let error = |text: &str| text.parse::<Expression>().unwrap_err();
+    assert!(error("z + w").contains("'w' at column 5"));
+    assert!(error("sin z").contains("'sin' at column 1 needs an argument"));
+    assert!(error("(z + 1").contains("'(' at column 1 is never closed"));
+    assert!(error("z + ").contains("ends too early"));
+    assert!(error("z^2 ) + c").contains("')' at column 5"));
+    assert!(error("1.2.3 z").contains("'1.2.3'"));
+    assert!(error("z + p17").contains("'p17'"));
+
+    // Long chains fail cleanly instead of overflowing the stack.
+    assert!(error(&"z^".repeat(120_000)).contains("too deep"));
+    assert_eq!(
+        parse(&format!("{}z + c", "-".repeat(120_000))),
+        parse("z + c")
+    );
+    assert_eq!(
+        parse(&format!("{}z + c", "-".repeat(120_001))),
+        parse("-z + c")
+    );
+}
+
+#[test]
+fn undefined_values_escape() {
+    // 0 / 0 on the first step.
+    let formula = custom("z / z + c", "0", &[]);
+    let sample = formula.iterate((0.1, 0.1), 500, 4.0, false);
+    assert!(sample.iterations < 500.0);
+}
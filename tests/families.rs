@@ -0,0 +1,108 @@
+use mandelbrot_rs::collatz::CollatzJulia;
+use mandelbrot_rs::complex::{add, mul, norm_sq, Complex};
+use mandelbrot_rs::formula::Formula;
+use mandelbrot_rs::fractal::smooth_iterations;
+use mandelbrot_rs::magnet::{Magnet, MagnetJulia};
+use mandelbrot_rs::phoenix::Phoenix;
+use mandelbrot_rs::{mandelbrot_iterations, FormulaKind, MandelbrotParams, Sample};
+
+#[test]
+fn phoenix_steps_look_back_one_point() {
+    let p = (-0.5, 0.1);
+    let phoenix = Phoenix { p };
+    for c in [(0.3, 0.4), (-0.6, 0.7), (0.6, 0.5)] {
+        let (mut z, mut previous, mut iter) = ((0.0, 0.0), (0.0, 0.0), 0);
+        while norm_sq(z) <= 4.0 && iter < 300 {
+            (z, previous) = (add(add(mul(z, z), c), mul(p, previous)), z);
+            iter += 1;
+        }
+        assert!(iter < 300, "{c:?} did not escape");
+        assert_eq!(
+            phoenix.iterate(c, 300, 4.0, false),
+            Sample::escaped(smooth_iterations(iter, z.0, z.1))
+        );
+    }
+
+    // z returns to 1 on the third step, but the point before it differs, so
+    // this is no cycle: the orbit goes on to escape.
+    let returning = Phoenix { p: (-4.0, 0.0) };
+    assert_eq!(
+        returning.iterate((1.0, 0.0), 300, 4.0, false),
+        Sample::escaped(smooth_iterations(4, -6.0, 0.0))
+    );
+
+    // Without the memory term it is the Mandelbrot set.
+    let forgetful = Phoenix { p: (0.0, 0.0) };
+    for (x, y) in [(0.3, 0.0), (-1.3, 0.05), (-0.12, 0.75)] {
+        assert_eq!(
+            forgetful.iterate((x, y), 300, 4.0, false),
+            mandelbrot_iterations(x, y, 300, 4.0)
+        );
+    }
+}
+
+#[test]
+fn magnet_orbits_stop_at_the_fixed_point_one() {
+    for magnet in [Magnet::One, Magnet::Two] {
+        assert_eq!(magnet.step((1.0, 0.0), (0.3, -0.8)), (1.0, 0.0));
+
+        let julia = MagnetJulia {
+            magnet,
+            c: (0.3, -0.8),
+        };
+        // One is superattracting, so orbits close by arrive within a few steps.
+        let sample = julia.iterate((1.01, 0.01), 500, 4.0, false);
+        assert!(
+            sample.iterations > 0.0 && sample.iterations < 6.0,
+            "{sample:?}"
+        );
+        assert_eq!(sample.iterations.fract(), 0.0);
+
+        // Far out the magnet's own radius applies, not the view's.
+        let far = julia.iterate((50.0, 0.0), 500, 4.0, false);
+        assert!(far.iterations > 0.0 && far.iterations <= 1.0, "{far:?}");
+    }
+}
+
+#[test]
+fn collatz_map_extends_the_integer_one() {
+    let collatz = CollatzJulia { c: (0.0, 0.0) };
+    let step = |n: f64| collatz.step((n, 0.0), (0.0, 0.0));
+    for (n, next) in [
+        (6.0, 3.0),
+        (3.0, 10.0),
+        (10.0, 5.0),
+        (5.0, 16.0),
+        (1.0, 4.0),
+    ] {
+        let (re, im): Complex = step(n);
+        assert!(
+            (re - next).abs() < 1e-9 && im.abs() < 1e-9,
+            "{n} -> {re} {im}"
+        );
+    }
+
+    // Orbits escape by their imaginary part, however far along the real axis.
+    assert_eq!(collatz.iterate((0.0, 2.5), 100, 4.0, false).iterations, 0.0);
+    assert!(!collatz.escaped((100.0, 1.0), 4.0));
+}
+
+#[test]
+fn new_families_have_mandelbrot_and_julia_forms() {
+    let params = MandelbrotParams::default();
+    for (name, kind) in [
+        ("phoenix", FormulaKind::Phoenix),
+        ("magnet-1", FormulaKind::MagnetI),
+        ("magnet-2", FormulaKind::MagnetII),
+        ("collatz", FormulaKind::Collatz),
+    ] {
+        let julia: FormulaKind = format!("{name}-julia").parse().unwrap();
+        assert_eq!(name.parse(), Ok(kind));
+        assert!(!kind.is_julia() && julia.is_julia());
+        assert_eq!(julia.build(&params).initial((0.2, 0.3)).0, (0.2, 0.3));
+        assert_eq!(
+            kind.build(&params).initial((0.2, 0.3)),
+            ((0.0, 0.0), (0.2, 0.3))
+        );
+    }
+}
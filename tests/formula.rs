@@ -0,0 +1,72 @@
+use mandelbrot_rs::formula::{Complex, Formula};
+use mandelbrot_rs::{
+    julia_iterations, mandelbrot_iterations, FormulaKind, MandelbrotParams, Renderer,
+};
+
+/// `z^2 + c` written from scratch, relying on every default of the trait.
+struct PlainMandelbrot;
+
+impl Formula for PlainMandelbrot {
+    fn initial(&self, point: Complex) -> (Complex, Complex) {
+        ((0.0, 0.0), point)
+    }
+
+    fn step(&self, z: Complex, c: Complex) -> Complex {
+        (z.0 * z.0 - z.1 * z.1 + c.0, 2.0 * z.0 * z.1 + c.1)
+    }
+}
+
+#[test]
+fn built_in_formulas_match_the_iteration_functions() {
+    let params = MandelbrotParams::default();
+    let mandelbrot = FormulaKind::Mandelbrot.build(&params);
+    let julia = FormulaKind::Julia.build(&params);
+    for (x, y) in [(-0.75, 0.1), (0.3, 0.0), (-1.3, 0.05), (0.1, 0.6)] {
+        assert_eq!(
+            mandelbrot.iterate((x, y), 300, 4.0, false),
+            mandelbrot_iterations(x, y, 300, 4.0)
+        );
+        assert_eq!(
+            julia.iterate((x, y), 300, 4.0, false),
+            julia_iterations(x, y, params.julia_c_real, params.julia_c_imag, 300, 4.0)
+        );
+    }
+}
+
+#[test]
+fn default_loop_agrees_with_the_specialized_one() {
+    // Only points outside the cardioid, which the built-in formula skips.
+    for (x, y) in [(0.3, 0.0), (-1.3, 0.05), (-0.12, 0.75), (-0.745, 0.11)] {
+        let plain = PlainMandelbrot.iterate((x, y), 500, 4.0, false);
+        assert_eq!(plain, mandelbrot_iterations(x, y, 500, 4.0));
+        // Without a derivative there is nothing to estimate.
+        let with_distance = PlainMandelbrot.iterate((x, y), 500, 4.0, true);
+        assert_eq!(with_distance, plain);
+    }
+}
+
+#[test]
+fn julia_formula_renders_the_julia_set() {
+    let renderer = Renderer::new(40, 30);
+    let params = MandelbrotParams {
+        formula: FormulaKind::Julia,
+        ..MandelbrotParams::default()
+    };
+    let frame = renderer.render(&params);
+
+    let (center_x, center_y) = params.center_f64();
+    let (x, y) = (7, 21);
+    let point = (
+        center_x + (x as f64 - 20.0) / params.zoom,
+        center_y + (y as f64 - 15.0) / params.zoom,
+    );
+    let expected = julia_iterations(
+        point.0,
+        point.1,
+        params.julia_c_real,
+        params.julia_c_imag,
+        params.effective_max_iter(),
+        params.escape_radius * params.escape_radius,
+    );
+    assert_eq!(frame.iterations[y * 40 + x], expected.iterations);
+}
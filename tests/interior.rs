@@ -0,0 +1,69 @@
+use mandelbrot_rs::fractal::mandelbrot_distance;
+use mandelbrot_rs::{InteriorColoring, MandelbrotParams, Renderer};
+
+fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+    let error = (actual.0 - expected.0).hypot(actual.1 - expected.1);
+    assert!(error < 1e-9, "{actual:?} vs {expected:?}");
+}
+
+#[test]
+fn multipliers_of_cardioid_and_bulb_match_closed_forms() {
+    // The fixed point has multiplier 1 - sqrt(1 - 4c), with 1 - 4c = 1.75 - i here.
+    let sample = mandelbrot_distance(-0.1875, 0.25, 1000, 4.0);
+    assert_eq!(sample.period, 1);
+    let root = (1.75f64, -1.0f64);
+    let modulus = root.0.hypot(root.1);
+    let sqrt = (
+        ((modulus + root.0) / 2.0).sqrt(),
+        -((modulus - root.0) / 2.0).sqrt(),
+    );
+    assert_close(sample.multiplier, (1.0 - sqrt.0, -sqrt.1));
+
+    // The 2-cycle has multiplier 4 (c + 1).
+    let sample = mandelbrot_distance(-1.1, 0.05, 1000, 4.0);
+    assert_eq!(sample.period, 2);
+    assert_close(sample.multiplier, (4.0 * -0.1, 4.0 * 0.05));
+}
+
+#[test]
+fn cycles_found_by_iteration_are_attracting() {
+    // Inside the period-3 component above the cardioid, which is iterated.
+    let sample = mandelbrot_distance(-0.12, 0.75, 1000, 4.0);
+    assert_eq!(sample.period, 3);
+    let magnitude = sample.multiplier.0.hypot(sample.multiplier.1);
+    assert!(magnitude > 0.0 && magnitude < 1.0);
+    assert!(sample.distance > 0.0);
+}
+
+#[test]
+fn interior_distance_is_within_bounds_of_true_distance() {
+    // The nuclei of the cardioid and the period-2 bulb are both 1/4 from the boundary.
+    for c_real in [0.0, -1.0] {
+        let sample = mandelbrot_distance(c_real, 0.0, 1000, 4.0);
+        let ratio = sample.distance / 0.25;
+        assert!((1.0..=4.0).contains(&ratio), "c = {c_real}: ratio {ratio}");
+    }
+}
+
+#[test]
+fn interior_modes_recolor_without_changing_iterations() {
+    let renderer = Renderer::new(48, 36);
+    let black = renderer.render(&MandelbrotParams::default());
+    let params = MandelbrotParams {
+        interior: InteriorColoring::Period,
+        ..MandelbrotParams::default()
+    };
+    let mut frame = renderer.render(&params);
+    assert_eq!(frame.iterations, black.iterations);
+    // The middle of the view is inside the cardioid.
+    assert_ne!(frame.pixels[18 * 48 + 24], 0x000000);
+
+    let multiplier = MandelbrotParams {
+        interior: InteriorColoring::Multiplier,
+        ..params.clone()
+    };
+    assert!(params.same_samples(&multiplier));
+    assert!(!params.same_samples(&MandelbrotParams::default()));
+    frame.recolor(&multiplier);
+    assert_eq!(frame.pixels, renderer.render(&multiplier).pixels);
+}
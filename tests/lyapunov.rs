@@ -0,0 +1,79 @@
+use mandelbrot_rs::color::colorize_lyapunov;
+use mandelbrot_rs::lyapunov::{Lyapunov, Sequence};
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+
+fn lyapunov(sequence: &str) -> Lyapunov {
+    Lyapunov {
+        sequence: sequence.parse().unwrap(),
+    }
+}
+
+#[test]
+fn sequences_parse_from_as_and_bs() {
+    let sequence: Sequence = "aab AB".parse().unwrap();
+    assert_eq!(sequence.to_string(), "AABAB");
+    assert_eq!(Sequence::default().to_string(), "AB");
+
+    assert_eq!(
+        "ABC".parse::<Sequence>(),
+        Err("unexpected 'C' at column 3".to_string())
+    );
+    assert!(" ".parse::<Sequence>().is_err());
+    assert!("AB".repeat(40).parse::<Sequence>().is_err());
+}
+
+#[test]
+fn exponents_match_the_logistic_map() {
+    // x -> 2.5 x (1 - x) settles on 0.6, where the slope is -0.5.
+    let stable = lyapunov("A").exponent((2.5, 0.0), 1000);
+    assert!((stable - 0.5f64.ln()).abs() < 1e-9, "{stable}");
+
+    // Chaotic orbits spread apart, though never faster than at rate 4.
+    let chaotic = lyapunov("A").exponent((3.9, 0.0), 100_000);
+    assert!(chaotic > 0.4 && chaotic < 2f64.ln(), "{chaotic}");
+
+    // Orbits with rates beyond 4 leave the unit interval for good.
+    assert_eq!(lyapunov("AB").exponent((3.0, 4.5), 1000), f64::INFINITY);
+}
+
+#[test]
+fn sequences_pick_the_rate_of_each_step() {
+    let point = (3.3, 3.7);
+    assert_eq!(
+        lyapunov("A").exponent((3.3, 0.0), 1000),
+        lyapunov("AAA").exponent(point, 1000)
+    );
+    assert_eq!(
+        lyapunov("B").exponent((0.0, 3.7), 1000),
+        lyapunov("BB").exponent(point, 1000)
+    );
+    assert_ne!(
+        lyapunov("AB").exponent(point, 1000),
+        lyapunov("A").exponent(point, 1000)
+    );
+}
+
+#[test]
+fn stable_and_chaotic_orbits_get_separate_gradients() {
+    let channels = |color: u32| [(color >> 16) & 0xFF, (color >> 8) & 0xFF, color & 0xFF];
+    assert_eq!(colorize_lyapunov(0.0, 1.0), 0x000000);
+    let [red, _, blue] = channels(colorize_lyapunov(-1.0, 1.0));
+    assert!(red > blue);
+    let [red, _, blue] = channels(colorize_lyapunov(1.0, 1.0));
+    assert!(blue > red);
+    assert_eq!(colorize_lyapunov(f64::INFINITY, 1.0), 0xC8E8FF);
+
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::Lyapunov,
+        max_iter: 100,
+        ..MandelbrotParams::default()
+    };
+    params.reset_view();
+    params.zoom = 8.0;
+    // From a = b = 0.2 at the top left to 6.2 at the bottom right.
+    let frame = Renderer::new(48, 48).render(&params);
+    let exponent = |x: usize, y: usize| frame.exponents[y * 48 + x].unwrap();
+    assert!(exponent(16, 16) < 0.0);
+    assert_eq!(exponent(47, 47), f64::INFINITY);
+    assert_eq!(frame.pixels[47 * 48 + 47], 0xC8E8FF);
+}
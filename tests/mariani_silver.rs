@@ -0,0 +1,88 @@
+use mandelbrot_rs::{FormulaKind, InteriorColoring, MandelbrotParams, Renderer};
+
+fn assert_matches_brute_force(renderer: Renderer, params: MandelbrotParams) {
+    let brute_force = renderer.render(&params);
+    let subdivided = MandelbrotParams {
+        mariani_silver: true,
+        ..params.clone()
+    };
+
+    let single = renderer.render(&subdivided);
+    let progressive = renderer
+        .render_progressive(&subdivided, &|| false, &mut |_| {})
+        .unwrap();
+    // Periods are not compared: filled pixels take the period found on the border.
+    for frame in [single, progressive] {
+        assert_eq!(frame.iterations, brute_force.iterations);
+        assert_eq!(frame.pixels, brute_force.pixels);
+    }
+}
+
+#[test]
+fn mandelbrot_matches_brute_force() {
+    assert_matches_brute_force(Renderer::new(160, 120), MandelbrotParams::default());
+
+    let mut seahorse = MandelbrotParams {
+        zoom: 4000.0,
+        ..MandelbrotParams::default()
+    };
+    seahorse.set_center_f64(-0.745, 0.11);
+    assert_matches_brute_force(Renderer::new(130, 97), seahorse);
+}
+
+#[test]
+fn julia_matches_brute_force() {
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::Julia,
+        julia_c_real: -0.12,
+        julia_c_imag: 0.75,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(0.0, 0.0);
+    assert_matches_brute_force(Renderer::new(160, 120), params);
+}
+
+#[test]
+fn interior_colorings_match_brute_force() {
+    for interior in InteriorColoring::ALL {
+        let params = MandelbrotParams {
+            interior,
+            ..MandelbrotParams::default()
+        };
+        let brute_force = Renderer::new(96, 72).render(&params);
+        let subdivided = Renderer::new(96, 72).render(&MandelbrotParams {
+            mariani_silver: true,
+            ..params
+        });
+        assert_eq!(subdivided.pixels, brute_force.pixels, "{interior:?}");
+    }
+}
+
+#[test]
+fn formulas_with_holes_are_not_subdivided() {
+    // The pole at 0.3 is surrounded by bounded orbits but escapes itself.
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::CustomJulia,
+        expression: "z^2 + c + p1/(z - 0.3)".parse().unwrap(),
+        expression_parameters: vec![0.05],
+        julia_c_real: 0.0,
+        julia_c_imag: 0.0,
+        zoom: 1000.0,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(0.3, 0.0);
+    assert!(!params.can_subdivide());
+    assert_matches_brute_force(Renderer::new(128, 128), params);
+
+    let fractional = MandelbrotParams {
+        formula: FormulaKind::Multibrot,
+        power: 2.5,
+        ..MandelbrotParams::default()
+    };
+    assert!(!fractional.can_subdivide());
+    assert!(MandelbrotParams {
+        power: 3.0,
+        ..fractional
+    }
+    .can_subdivide());
+}
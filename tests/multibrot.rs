@@ -0,0 +1,95 @@
+use mandelbrot_rs::formula::{Formula, Multibrot, MultibrotJulia};
+use mandelbrot_rs::{
+    julia_iterations, mandelbrot_iterations, FormulaKind, MandelbrotParams, Renderer,
+};
+
+#[test]
+fn power_two_matches_the_quadratic_formulas() {
+    let multibrot = Multibrot { power: 2.0 };
+    let julia = MultibrotJulia {
+        power: 2.0,
+        c: (-0.7, 0.27015),
+    };
+    // Only points outside the cardioid, which the quadratic formula skips.
+    for (x, y) in [(0.3, 0.0), (-1.3, 0.05), (-0.12, 0.75), (-0.745, 0.11)] {
+        assert_eq!(
+            multibrot.iterate((x, y), 500, 4.0, false),
+            mandelbrot_iterations(x, y, 500, 4.0)
+        );
+        assert_eq!(
+            julia.iterate((x, y), 500, 4.0, false),
+            julia_iterations(x, y, -0.7, 0.27015, 500, 4.0)
+        );
+    }
+}
+
+#[test]
+fn smoothing_is_continuous_for_other_powers() {
+    for power in [3.0, 4.5] {
+        let multibrot = Multibrot { power };
+        // A line through the escaping region, crossing many iteration bands.
+        let values: Vec<f64> = (0..2000)
+            .map(|i| {
+                let x = 1.0 + i as f64 * 1e-3;
+                multibrot.iterate((x, 0.3), 200, 1e6, false).iterations
+            })
+            .collect();
+        assert!(values.first() > values.last());
+        for pair in values.windows(2) {
+            assert!(
+                (pair[0] - pair[1]).abs() < 0.1,
+                "power {power}: jump from {} to {}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+}
+
+#[test]
+fn negative_powers_keep_counts_within_their_escape_band() {
+    let multibrot = Multibrot { power: -2.0 };
+    let escape_step = |point| {
+        let (mut z, c) = multibrot.initial(point);
+        let mut steps = 0;
+        while !multibrot.escaped(z, 4.0) && steps < 50 {
+            z = multibrot.step(z, c);
+            steps += 1;
+        }
+        steps
+    };
+    // A line crossing bands the orbits leave by passing near the pole at 0.
+    let points: Vec<(u32, f64)> = (0..4000)
+        .map(|i| {
+            let point = (-2.0 + i as f64 * 1e-3, 0.5);
+            let steps = escape_step(point);
+            (steps, multibrot.iterate(point, 50, 4.0, false).iterations)
+        })
+        .filter(|&(steps, _)| steps < 50)
+        .collect();
+    for &(steps, value) in &points {
+        assert!((steps as f64..=steps as f64 + 1.0).contains(&value));
+    }
+    for pair in points.windows(2) {
+        let [(steps_a, a), (steps_b, b)] = [pair[0], pair[1]];
+        if steps_a.abs_diff(steps_b) <= 1 {
+            assert!((a - b).abs() <= 1.0, "jump from {a} to {b}");
+        }
+    }
+}
+
+#[test]
+fn negative_powers_render_finite_values() {
+    let renderer = Renderer::new(40, 30);
+    let params = MandelbrotParams {
+        formula: FormulaKind::Multibrot,
+        power: -2.0,
+        ..MandelbrotParams::default()
+    };
+    let frame = renderer.render(&params);
+    assert!(frame.iterations.iter().all(|value| value.is_finite()));
+    assert!(frame
+        .iterations
+        .iter()
+        .any(|&value| value < params.effective_max_iter() as f64));
+}
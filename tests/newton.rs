@@ -0,0 +1,131 @@
+use mandelbrot_rs::bigfloat::BigFloat;
+use mandelbrot_rs::complex::{norm_sq, sub, Complex};
+use mandelbrot_rs::formula::Formula;
+use mandelbrot_rs::newton::{Newton, NovaJulia};
+use mandelbrot_rs::polynomial::Polynomial;
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+
+fn parse(text: &str) -> Polynomial {
+    text.parse().unwrap()
+}
+
+#[test]
+fn polynomials_parse_from_sums_products_and_powers() {
+    assert_eq!(
+        parse("z^3 - 1").coefficients(),
+        [(-1.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 0.0)]
+    );
+    assert_eq!(
+        parse("2z^2 + 3i*z - 0.5").coefficients(),
+        [(-0.5, 0.0), (0.0, 3.0), (2.0, 0.0)]
+    );
+    assert_eq!(
+        parse("(z - 1)(z + 2i)"),
+        Polynomial::from_roots(&[(1.0, 0.0), (0.0, -2.0)])
+    );
+    assert_eq!(parse("-z^2"), parse("-1 * z * z"));
+
+    let polynomial = parse("(z - 1)(z + 0.5 - 2i)^2 - 3iz");
+    assert_eq!(parse(&polynomial.to_string()), polynomial);
+
+    for invalid in ["z^", "z^1.5", "(z - 1", "z + w", "", "z^65"] {
+        assert!(invalid.parse::<Polynomial>().is_err(), "{invalid:?} parsed");
+    }
+
+    // Deep or huge inputs fail quickly instead of overflowing the stack.
+    let error = |text: &str| text.parse::<Polynomial>().unwrap_err();
+    assert!(error("((z^64)^64)^64").contains("degree 4096 is above"));
+    assert!(error(&"(z^64)".repeat(20_000)).contains("degree 128 is above"));
+    assert!(
+        error(&format!("{}z{}", "(".repeat(120_000), ")".repeat(120_000))).contains("too deep")
+    );
+    assert_eq!(parse(&format!("{}z", "-".repeat(120_000))), parse("z"));
+}
+
+#[test]
+fn roots_are_found_once_each() {
+    let roots = parse("z^3 - 1").roots();
+    let expected = [
+        (1.0, 0.0),
+        (-0.5, 0.75f64.sqrt()),
+        (-0.5, -(0.75f64.sqrt())),
+    ];
+    assert_eq!(roots.len(), 3);
+    for root in expected {
+        assert!(roots.iter().any(|&found| norm_sq(sub(found, root)) < 1e-24));
+    }
+
+    // The double root comes out once.
+    assert_eq!(parse("(z - 1)^2 (z + 2)").roots().len(), 2);
+}
+
+#[test]
+fn orbits_converge_to_the_nearest_root() {
+    for relaxation in [1.0, 0.6] {
+        let newton = Newton::new(parse("z^4 - 1"), relaxation);
+        for (index, &root) in newton.roots().iter().enumerate() {
+            let near: Complex = (root.0 * 0.9 + 0.05, root.1 * 0.9 - 0.05);
+            let sample = newton.iterate(near, 500, 4.0, false);
+            assert_eq!(sample.root, Some(index as u32));
+            assert!(sample.iterations < 500.0);
+        }
+    }
+}
+
+#[test]
+fn smoothed_convergence_counts_are_continuous() {
+    let newton = Newton::new(parse("z^3 - 1"), 1.0);
+    // Inside the basin of 1, away from the basin boundaries.
+    let counts: Vec<f64> = (0..1000)
+        .map(|i| {
+            newton
+                .iterate((1.2 + i as f64 * 2e-3, 0.1), 500, 4.0, false)
+                .iterations
+        })
+        .collect();
+    for pair in counts.windows(2) {
+        assert!(
+            (pair[0] - pair[1]).abs() < 0.05,
+            "{} -> {}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn nova_julia_without_a_shift_is_newton_without_roots() {
+    let newton = Newton::new(parse("z^3 - 1"), 1.0);
+    let nova = NovaJulia {
+        polynomial: parse("z^3 - 1"),
+        relaxation: 1.0,
+        c: (0.0, 0.0),
+    };
+    for point in [(0.3, 0.8), (-1.2, 0.1), (2.0, -1.5)] {
+        let converged = newton.iterate(point, 500, 4.0, false);
+        let shifted = nova.iterate(point, 500, 4.0, false);
+        assert_eq!(shifted.iterations, converged.iterations);
+        assert_eq!(shifted.root, None);
+    }
+}
+
+#[test]
+fn newton_renders_ignore_the_escape_radius() {
+    let renderer = Renderer::new(40, 30);
+    let params = MandelbrotParams {
+        formula: FormulaKind::Newton,
+        center_x: BigFloat::from_f64(0.0),
+        ..MandelbrotParams::default()
+    };
+    let frame = renderer.render(&params);
+    let wide = renderer.render(&MandelbrotParams {
+        escape_radius: 10.0,
+        ..params
+    });
+    assert_eq!(frame.iterations, wide.iterations);
+
+    let mut roots: Vec<u32> = frame.roots.iter().flatten().copied().collect();
+    roots.sort();
+    roots.dedup();
+    assert_eq!(roots, [0, 1, 2]);
+}
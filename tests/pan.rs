@@ -0,0 +1,53 @@
+use mandelbrot_rs::{MandelbrotParams, Renderer};
+
+fn params_at(zoom: f64) -> MandelbrotParams {
+    MandelbrotParams {
+        zoom,
+        ..MandelbrotParams::default()
+    }
+}
+
+#[test]
+fn panned_render_matches_full_render() {
+    let renderer = Renderer::new(64, 48);
+    let from = params_at(256.0);
+    let previous = renderer.render(&from);
+
+    // Power-of-two zoom keeps the pixel grid exact, so reused and fresh
+    // samples land on the same points.
+    let mut to = from.clone();
+    to.pan(-5.0 / 256.0, 3.0 / 256.0);
+
+    let offset = renderer.pan_offset(&from, &to).unwrap();
+    assert_eq!(offset, (-5, 3));
+
+    let panned = renderer
+        .render_panned(&to, &previous, offset, &|| false)
+        .unwrap();
+    let full = renderer.render(&to);
+    assert_eq!(panned.iterations, full.iterations);
+    assert_eq!(panned.periods, full.periods);
+    assert_eq!(panned.pixels, full.pixels);
+}
+
+#[test]
+fn pan_offset_requires_whole_pixel_translation() {
+    let renderer = Renderer::new(64, 48);
+    let from = params_at(256.0);
+
+    let mut fractional = from.clone();
+    fractional.pan(0.5 / 256.0, 0.0);
+    assert_eq!(renderer.pan_offset(&from, &fractional), None);
+
+    let mut too_far = from.clone();
+    too_far.pan(64.0 / 256.0, 0.0);
+    assert_eq!(renderer.pan_offset(&from, &too_far), None);
+
+    let mut zoomed = from.clone();
+    zoomed.zoom = 512.0;
+    assert_eq!(renderer.pan_offset(&from, &zoomed), None);
+
+    let mut recolored = from.clone();
+    recolored.color_offset = 0.25;
+    assert_eq!(renderer.pan_offset(&from, &recolored), None);
+}
@@ -0,0 +1,57 @@
+use mandelbrot_rs::bigfloat::BigFloat;
+use mandelbrot_rs::double_double::DoubleDouble;
+use mandelbrot_rs::fractal::{julia_iterations, mandelbrot_iterations, mandelbrot_iterations_dd};
+use mandelbrot_rs::perturbation::ReferenceOrbit;
+
+// The huge iteration limits only finish because the cycle is detected early.
+#[test]
+fn interior_points_report_their_period() {
+    let period_3_bulb = mandelbrot_iterations(-0.12, 0.75, u32::MAX, 4.0);
+    assert_eq!(period_3_bulb.iterations, u32::MAX as f64);
+    assert_eq!(period_3_bulb.period, 3);
+
+    let period_4_bulb = mandelbrot_iterations(-1.31, 0.0, u32::MAX, 4.0);
+    assert_eq!(period_4_bulb.period, 4);
+
+    // The basilica's critical orbit 0 -> -1 -> 0 is a superattracting 2-cycle.
+    let basilica = julia_iterations(0.0, 0.0, -1.0, 0.0, u32::MAX, 4.0);
+    assert_eq!(basilica.iterations, u32::MAX as f64);
+    assert_eq!(basilica.period, 2);
+}
+
+#[test]
+fn deep_zoom_kernels_leave_escaping_points_exterior() {
+    // Near the tip of the antenna orbits crawl away from 2 by steps far
+    // below any fixed cycle tolerance, yet escape at step 34.
+    let max_iter = 1000;
+    let (x, y) = ("-1.99999999999999999999", "5e-20");
+    let center = (
+        x.parse::<BigFloat>().unwrap(),
+        y.parse::<BigFloat>().unwrap(),
+    );
+    let reference =
+        ReferenceOrbit::mandelbrot(&center.0, &center.1, max_iter, 4.0, 192, &|| false).unwrap();
+    let dd = (
+        DoubleDouble::from_bigfloat(&center.0),
+        DoubleDouble::from_bigfloat(&center.1),
+    );
+
+    for k in 0..4 {
+        let dx = k as f64 * 1e-21;
+        let samples = [
+            mandelbrot_iterations_dd(dd.0 + DoubleDouble::from_f64(dx), dd.1, max_iter, 4.0),
+            reference.iterations(dx, 0.0, max_iter, 4.0),
+        ];
+        for sample in samples {
+            assert_eq!(sample.period, 0, "{sample:?}");
+            assert!((33.0..35.0).contains(&sample.iterations), "{sample:?}");
+        }
+    }
+}
+
+#[test]
+fn escaping_points_have_no_period() {
+    let sample = mandelbrot_iterations(0.3, 0.0, 500, 4.0);
+    assert!(sample.iterations < 500.0);
+    assert_eq!(sample.period, 0);
+}
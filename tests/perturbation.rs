@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use mandelbrot_rs::bigfloat::BigFloat;
+use mandelbrot_rs::perturbation::ReferenceOrbit;
+use mandelbrot_rs::{
+    julia_iterations, mandelbrot_iterations, Coloring, FormulaKind, MandelbrotParams, Precision,
+    Renderer, Sample,
+};
+
+const MAX_ITER: u32 = 500;
+
+fn assert_same_counts(actual: Sample, expected: Sample, at: (f64, f64)) {
+    let tolerance = 1e-6 * expected.iterations.max(1.0);
+    assert!(
+        (actual.iterations - expected.iterations).abs() < tolerance,
+        "{actual:?} vs {expected:?} at {at:?}"
+    );
+}
+
+/// Renders `params` with perturbation and with plain `f64`, and compares
+/// the iteration counts pixel by pixel.
+fn assert_perturbation_matches_f64(params: MandelbrotParams) {
+    let renderer = Renderer::new(64, 48);
+    let expected = renderer.render(&MandelbrotParams {
+        precision: Precision::Double,
+        coloring: Coloring::DistanceEstimate,
+        ..params.clone()
+    });
+    let actual = renderer.render(&MandelbrotParams {
+        precision: Precision::Perturbation,
+        coloring: Coloring::DistanceEstimate,
+        ..params.clone()
+    });
+
+    let mut compared = 0;
+    for (index, (a, b)) in actual
+        .iterations
+        .iter()
+        .zip(&expected.iterations)
+        .enumerate()
+    {
+        // As for distances, orbits of points hugging the boundary are chaotic.
+        if *b < expected.max_iter as f64 && expected.distances[index] * params.zoom < 0.1 {
+            continue;
+        }
+        assert!((a - b).abs() < 1e-6 * b, "pixel {index}: {a} vs {b}");
+        compared += 1;
+    }
+    assert!(
+        compared > actual.iterations.len() / 2,
+        "only {compared} compared"
+    );
+}
+
+#[test]
+fn mandelbrot_counts_match_f64() {
+    let mut params = MandelbrotParams {
+        zoom: 4000.0,
+        max_iter: MAX_ITER,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(-0.745, 0.11);
+    assert_perturbation_matches_f64(params);
+}
+
+#[test]
+fn julia_counts_match_f64() {
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::Julia,
+        julia_c_real: -0.8,
+        julia_c_imag: 0.156,
+        zoom: 2000.0,
+        max_iter: MAX_ITER,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(0.31, 0.05);
+    assert_perturbation_matches_f64(params);
+}
+
+#[test]
+fn pixels_continue_on_the_critical_orbit_once_the_reference_escapes() {
+    // Both center orbits escape within a dozen steps, while most of the
+    // pixels close by are inside the set and must carry on along the
+    // critical orbit. The offsets are small enough that no glitch comes first.
+    let mandelbrot_center = (0.3, 0.0);
+    let julia_center = (1.62, 0.0);
+    let c = (-1.0, 0.0);
+    let big = |(x, y): (f64, f64)| (BigFloat::from_f64(x), BigFloat::from_f64(y));
+    let (x, y) = big(mandelbrot_center);
+    let mandelbrot = ReferenceOrbit::mandelbrot(&x, &y, MAX_ITER, 4.0, 128, &|| false).unwrap();
+    let (x, y) = big(julia_center);
+    let julia = ReferenceOrbit::julia(&x, &y, c.0, c.1, MAX_ITER, 4.0, 128, &|| false).unwrap();
+
+    let mut inside = 0;
+    for i in 0..=20 {
+        let dx = -0.004 * i as f64;
+        for dy in [0.0, 0.001, -0.003] {
+            let at = (mandelbrot_center.0 + dx, mandelbrot_center.1 + dy);
+            let expected = mandelbrot_iterations(at.0, at.1, MAX_ITER, 4.0);
+            assert_same_counts(mandelbrot.iterations(dx, dy, MAX_ITER, 4.0), expected, at);
+
+            let at = (julia_center.0 + dx, julia_center.1 + dy);
+            let expected = julia_iterations(at.0, at.1, c.0, c.1, MAX_ITER, 4.0);
+            assert_same_counts(julia.iterations(dx, dy, MAX_ITER, 4.0), expected, at);
+            if expected.iterations == MAX_ITER as f64 {
+                inside += 1;
+            }
+        }
+    }
+    assert!(inside > 20);
+    assert_eq!(
+        mandelbrot.iterations(-0.06, 0.0, MAX_ITER, 4.0),
+        Sample::interior(MAX_ITER, 0)
+    );
+}
+
+#[test]
+fn reference_orbits_stop_when_cancelled() {
+    // Inside the set, so the orbit would run the full million steps.
+    let (x, y) = (BigFloat::from_f64(-0.12), BigFloat::from_f64(0.75));
+    let polls = AtomicU32::new(0);
+    let cancelled = || polls.fetch_add(1, Ordering::Relaxed) >= 100;
+    assert!(ReferenceOrbit::mandelbrot(&x, &y, 1_000_000, 4.0, 1024, &cancelled).is_none());
+    assert_eq!(polls.load(Ordering::Relaxed), 101);
+
+    polls.store(0, Ordering::Relaxed);
+    assert!(ReferenceOrbit::julia(&x, &y, -1.0, 0.0, 1_000_000, 4.0, 1024, &cancelled).is_none());
+    assert_eq!(polls.load(Ordering::Relaxed), 101);
+}
@@ -0,0 +1,64 @@
+use std::sync::Mutex;
+
+use mandelbrot_rs::{MandelbrotParams, Renderer};
+
+#[test]
+fn progressive_render_matches_single_pass() {
+    let renderer = Renderer::new(61, 45);
+    let params = MandelbrotParams::default();
+
+    let mut previews = Vec::new();
+    let progressive = renderer
+        .render_progressive(&params, &|| false, &mut |frame| {
+            previews.push(frame.clone())
+        })
+        .unwrap();
+    let single = renderer.render(&params);
+
+    assert_eq!(previews.len(), 3);
+    assert_eq!(progressive.iterations, single.iterations);
+    assert_eq!(progressive.pixels, single.pixels);
+
+    // The 1/8 preview repeats each sample over its 8x8 block.
+    let coarse = &previews[0];
+    assert_eq!(
+        coarse.iterations[9 * 61 + 13],
+        single.iterations[8 * 61 + 8]
+    );
+}
+
+#[test]
+fn cancelled_render_returns_none() {
+    let renderer = Renderer::new(40, 30);
+    let params = MandelbrotParams::default();
+    assert!(renderer
+        .render_progressive(&params, &|| true, &mut |_| {})
+        .is_none());
+}
+
+#[test]
+fn final_pass_tiles_cover_the_frame() {
+    // Not a multiple of the tile size, so the edge tiles are partial.
+    let renderer = Renderer::new(150, 70);
+    let params = MandelbrotParams::default();
+
+    let tiles = Mutex::new(Vec::new());
+    let frame = renderer
+        .render_progressive_tiles(&params, &|| false, &mut |_| {}, &|tile| {
+            tiles.lock().unwrap().push(tile.clone())
+        })
+        .unwrap();
+
+    let mut assembled = vec![None; 150 * 70];
+    for tile in tiles.into_inner().unwrap() {
+        for (row, pixels) in tile.pixels.chunks(tile.width).enumerate() {
+            for (column, &pixel) in pixels.iter().enumerate() {
+                let index = (tile.y + row) * 150 + tile.x + column;
+                assert!(assembled[index].is_none(), "tiles overlap");
+                assembled[index] = Some(pixel);
+            }
+        }
+    }
+    let assembled: Vec<u32> = assembled.into_iter().map(Option::unwrap).collect();
+    assert_eq!(assembled, frame.pixels);
+}
@@ -0,0 +1,31 @@
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+
+#[test]
+fn recolor_matches_fresh_render() {
+    let renderer = Renderer::new(48, 36);
+    let params = MandelbrotParams::default();
+    let mut frame = renderer.render(&params);
+
+    let mut repainted = params.clone();
+    repainted.color_offset = 0.4;
+    repainted.color_scale = 2.5;
+    assert!(params.same_samples(&repainted));
+
+    frame.recolor(&repainted);
+    let fresh = renderer.render(&repainted);
+    assert_eq!(frame.iterations, fresh.iterations);
+    assert_eq!(frame.pixels, fresh.pixels);
+}
+
+#[test]
+fn same_samples_ignores_only_the_palette() {
+    let params = MandelbrotParams::default();
+
+    let mut deeper = params.clone();
+    deeper.max_iter += 1;
+    assert!(!params.same_samples(&deeper));
+
+    let mut julia = params.clone();
+    julia.formula = FormulaKind::Julia;
+    assert!(!params.same_samples(&julia));
+}
@@ -0,0 +1,50 @@
+use mandelbrot_rs::fractal::Sample;
+use mandelbrot_rs::simd::{julia_iterations_x4, mandelbrot_iterations_x4, LANES};
+use mandelbrot_rs::{julia_iterations, mandelbrot_iterations};
+
+/// Points spread over `[-2, 1] x [-1.2, 1.2]`, including boundary, cycle and
+/// cardioid points.
+fn grid() -> Vec<(f64, f64)> {
+    (0..96)
+        .flat_map(|y| (0..128).map(move |x| (x, y)))
+        .map(|(x, y)| (-2.0 + x as f64 * 3.0 / 128.0, -1.2 + y as f64 * 2.4 / 96.0))
+        .collect()
+}
+
+fn assert_identical(vector: &[Sample], scalar: &[Sample]) {
+    for (vector, scalar) in vector.iter().zip(scalar) {
+        assert_eq!(vector.iterations.to_bits(), scalar.iterations.to_bits());
+        assert_eq!(vector.period, scalar.period);
+    }
+}
+
+#[test]
+fn mandelbrot_x4_matches_scalar_bit_for_bit() {
+    for max_iter in [0, 1, 7, 300] {
+        for chunk in grid().chunks_exact(LANES) {
+            let real = std::array::from_fn(|lane| chunk[lane].0);
+            let imag = std::array::from_fn(|lane| chunk[lane].1);
+            let vector = mandelbrot_iterations_x4(real, imag, max_iter, 4.0);
+            let scalar: Vec<Sample> = chunk
+                .iter()
+                .map(|&(re, im)| mandelbrot_iterations(re, im, max_iter, 4.0))
+                .collect();
+            assert_identical(&vector, &scalar);
+        }
+    }
+}
+
+#[test]
+fn julia_x4_matches_scalar_bit_for_bit() {
+    for (c_real, c_imag) in [(-0.7, 0.27015), (-1.0, 0.0), (0.285, 0.01)] {
+        for chunk in grid().chunks_exact(LANES) {
+            let real = std::array::from_fn(|lane| chunk[lane].0 + 0.5);
+            let imag = std::array::from_fn(|lane| chunk[lane].1);
+            let vector = julia_iterations_x4(real, imag, c_real, c_imag, 300, 4.0);
+            let scalar: Vec<Sample> = (0..LANES)
+                .map(|lane| julia_iterations(real[lane], imag[lane], c_real, c_imag, 300, 4.0))
+                .collect();
+            assert_identical(&vector, &scalar);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use mandelbrot_rs::color::average_colors;
+use mandelbrot_rs::{
+    colorize_pixel, FormulaKind, MandelbrotParams, Renderer, Sample, Supersampling,
+};
+
+#[test]
+fn averaging_happens_in_linear_light() {
+    for value in 0..=255u32 {
+        let gray = value * 0x010101;
+        assert_eq!(average_colors(&[gray, gray]), gray);
+    }
+    // Half black, half white is 50% linear intensity, not sRGB 128.
+    assert_eq!(average_colors(&[0x000000, 0xFFFFFF]), 0xBCBCBC);
+}
+
+#[test]
+fn grid_matches_a_render_at_twice_the_resolution() {
+    let params = MandelbrotParams {
+        supersampling: Supersampling::Grid2x2,
+        zoom: 256.0,
+        ..MandelbrotParams::default()
+    };
+    let frame = Renderer::new(40, 30).render(&params);
+    let grids = frame.supersamples.as_ref().unwrap();
+
+    // At twice the size and zoom, and a quarter pixel up and left, pixel
+    // (2x + i, 2y + j) is grid point (i, j) of pixel (x, y).
+    let mut fine_params = MandelbrotParams {
+        zoom: params.zoom * 2.0,
+        supersampling: Supersampling::Off,
+        ..params.clone()
+    };
+    let (center_x, center_y) = params.center_f64();
+    let quarter = 0.25 / params.zoom;
+    fine_params.set_center_f64(
+        center_x - quarter,
+        center_y - quarter * params.imaginary_direction(),
+    );
+    let fine = Renderer::new(80, 60).render(&fine_params);
+
+    let max_iter = params.effective_max_iter();
+    for y in 0..30 {
+        for x in 0..40 {
+            let expected: Vec<Sample> = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                .iter()
+                .map(|&(i, j)| fine.sample((2 * y + j) * 80 + 2 * x + i))
+                .collect();
+            assert_eq!(grids[y * 40 + x], expected);
+
+            let colors: Vec<u32> = expected
+                .iter()
+                .map(|s| {
+                    colorize_pixel(
+                        s.iterations,
+                        max_iter,
+                        params.color_scale,
+                        params.color_offset,
+                    )
+                })
+                .collect();
+            assert_eq!(frame.pixels[y * 40 + x], average_colors(&colors));
+        }
+    }
+}
+
+#[test]
+fn adaptive_only_supersamples_high_contrast_pixels() {
+    let params = MandelbrotParams {
+        supersampling: Supersampling::Adaptive,
+        ..MandelbrotParams::default()
+    };
+    let frame = Renderer::new(80, 60).render(&params);
+    let grids = frame.supersamples.as_ref().unwrap();
+
+    let supersampled = grids.iter().filter(|s| !s.is_empty()).count();
+    assert!(supersampled > 0);
+    assert!(supersampled < 80 * 60 / 2);
+    for samples in grids.iter().filter(|s| !s.is_empty()) {
+        assert_eq!(samples.len(), 16);
+    }
+
+    // The main cardioid is flat, so its middle is left alone.
+    assert!(grids[30 * 80 + 40].is_empty());
+}
+
+#[test]
+fn adaptive_supersamples_lyapunov_edges() {
+    // Lyapunov samples carry an exponent rather than an iteration count.
+    let mut params = MandelbrotParams {
+        formula: FormulaKind::Lyapunov,
+        supersampling: Supersampling::Adaptive,
+        ..MandelbrotParams::default()
+    };
+    params.reset_view();
+    let frame = Renderer::new(80, 60).render(&params);
+    let supersampled = frame
+        .supersamples
+        .as_ref()
+        .unwrap()
+        .iter()
+        .filter(|s| !s.is_empty())
+        .count();
+    assert!(supersampled > 0);
+    assert!(supersampled < 80 * 60 / 2);
+}
+
+#[test]
+fn odd_grids_reuse_the_pixel_sample_in_their_middle() {
+    let params = MandelbrotParams {
+        supersampling: Supersampling::Grid3x3,
+        ..MandelbrotParams::default()
+    };
+    let frame = Renderer::new(40, 30).render(&params);
+    for (index, grid) in frame.supersamples.as_ref().unwrap().iter().enumerate() {
+        assert_eq!(grid.len(), 9);
+        assert_eq!(grid[4], frame.sample(index));
+    }
+
+    let off = Renderer::new(40, 30).render(&MandelbrotParams::default());
+    assert_eq!(off.supersamples, None);
+}
+
+#[test]
+fn panning_keeps_supersamples_in_place() {
+    let renderer = Renderer::new(64, 48);
+    let from = MandelbrotParams {
+        zoom: 256.0,
+        // Eighth-pixel offsets keep every grid point exact, as in the plain pan test.
+        supersampling: Supersampling::Grid4x4,
+        ..MandelbrotParams::default()
+    };
+    let previous = renderer.render(&from);
+
+    let mut to = from.clone();
+    to.pan(7.0 / 256.0, -2.0 / 256.0);
+    let offset = renderer.pan_offset(&from, &to).unwrap();
+    let panned = renderer
+        .render_panned(&to, &previous, offset, &|| false)
+        .unwrap();
+    let full = renderer.render(&to);
+    assert_eq!(panned.supersamples, full.supersamples);
+    assert_eq!(panned.pixels, full.pixels);
+}
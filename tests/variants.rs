@@ -0,0 +1,77 @@
+use mandelbrot_rs::formula::{Complex, Formula, Mandelbrot, Variant, VariantMandelbrot};
+use mandelbrot_rs::{FormulaKind, MandelbrotParams, Renderer};
+
+#[test]
+fn variants_fold_the_orbit_before_squaring() {
+    let square = |z: Complex| Mandelbrot.step(z, (0.0, 0.0));
+    let c = (0.25, -0.5);
+    for (x, y) in [(0.7, -1.3), (-0.4, 0.9), (-1.1, -0.2)] {
+        let step = |variant| VariantMandelbrot { variant }.step((x, y), c);
+        let plus_c = |z: Complex| (z.0 + c.0, z.1 + c.1);
+        let (re, im) = square((x, y));
+
+        assert_eq!(
+            step(Variant::BurningShip),
+            plus_c(square((x.abs(), y.abs())))
+        );
+        assert_eq!(step(Variant::Tricorn), plus_c(square((x, -y))));
+        assert_eq!(step(Variant::Celtic), plus_c((re.abs(), im)));
+        assert_eq!(step(Variant::Buffalo), plus_c((re.abs(), im.abs())));
+        assert_eq!(step(Variant::Perpendicular), plus_c(square((x.abs(), -y))));
+    }
+}
+
+#[test]
+fn tricorn_is_symmetric_about_the_real_axis() {
+    let tricorn = FormulaKind::Tricorn.build(&MandelbrotParams::default());
+    for (x, y) in [(-0.3, 0.6), (0.35, 0.2), (-1.05, 0.4), (0.1, 1.1)] {
+        assert_eq!(
+            tricorn.iterate((x, y), 300, 4.0, false),
+            tricorn.iterate((x, -y), 300, 4.0, false)
+        );
+    }
+}
+
+fn burning_ship(flip_imaginary: bool) -> MandelbrotParams {
+    MandelbrotParams {
+        formula: FormulaKind::BurningShip,
+        zoom: 64.0,
+        flip_imaginary,
+        ..MandelbrotParams::default()
+    }
+}
+
+#[test]
+fn flipping_the_imaginary_axis_mirrors_the_rows() {
+    let (width, height) = (40, 30);
+    let renderer = Renderer::new(width, height);
+    let down = renderer.render(&burning_ship(false));
+    let up = renderer.render(&burning_ship(true));
+
+    // Row y sits y - height / 2 rows from the center line, so rows y and
+    // height - y mirror each other.
+    for y in 1..height {
+        let row = |frame: &mandelbrot_rs::Frame, y: usize| {
+            frame.iterations[y * width..(y + 1) * width].to_vec()
+        };
+        assert_eq!(row(&up, y), row(&down, height - y));
+    }
+    assert_ne!(up.iterations, down.iterations);
+}
+
+#[test]
+fn panning_with_a_flipped_axis_reuses_the_right_rows() {
+    let renderer = Renderer::new(64, 48);
+    let from = burning_ship(true);
+    let previous = renderer.render(&from);
+
+    let mut to = from.clone();
+    to.pan(2.0 / 64.0, 3.0 / 64.0);
+    let offset = renderer.pan_offset(&from, &to).unwrap();
+    assert_eq!(offset, (2, -3));
+
+    let panned = renderer
+        .render_panned(&to, &previous, offset, &|| false)
+        .unwrap();
+    assert_eq!(panned.iterations, renderer.render(&to).iterations);
+}
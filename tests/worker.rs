@@ -0,0 +1,115 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mandelbrot_rs::worker::{RenderResult, RenderUpdate, RenderWorker};
+use mandelbrot_rs::{Frame, MandelbrotParams, Renderer};
+
+/// A render that takes far longer than cancelling it should.
+fn slow_job() -> (Renderer, MandelbrotParams) {
+    let mut params = MandelbrotParams {
+        zoom: 4000.0,
+        max_iter: 5000,
+        ..MandelbrotParams::default()
+    };
+    params.set_center_f64(-0.745, 0.11);
+    (Renderer::new(1600, 1200), params)
+}
+
+/// Collects results until the job `generation` completes.
+fn wait_for(worker: &RenderWorker, generation: u64) -> Vec<RenderResult> {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let mut results = Vec::new();
+    loop {
+        results.extend(worker.drain());
+        let done = results.iter().any(|result| {
+            result.generation == generation && matches!(result.update, RenderUpdate::Complete(_))
+        });
+        if done {
+            return results;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "job {generation} never completed"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// The complete frames among `results` with their generations, in order.
+fn completed(results: &[RenderResult]) -> Vec<(u64, &Frame)> {
+    results
+        .iter()
+        .filter_map(|result| match &result.update {
+            RenderUpdate::Complete(frame) => Some((result.generation, frame)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn newer_jobs_cancel_stale_ones() {
+    let (renderer, params) = slow_job();
+    // The same view with a hundredth of the pixels.
+    let start = Instant::now();
+    Renderer::new(renderer.width() / 10, renderer.height() / 10).render(&params);
+    let hundredth = start.elapsed();
+
+    let worker = RenderWorker::spawn(|| {});
+    let stale = worker.submit(renderer, params);
+    // Give the render thread time to start on the slow job.
+    thread::sleep(Duration::from_millis(50));
+    assert!(worker.is_busy());
+
+    let submitted = Instant::now();
+    let fresh = worker.submit(Renderer::new(32, 24), MandelbrotParams::default());
+    let results = wait_for(&worker, fresh);
+    let waited = submitted.elapsed();
+    // Finishing the stale job first would take a good fraction of a hundred times as long.
+    assert!(
+        waited < hundredth * 25,
+        "waited {waited:?} behind a stale job"
+    );
+    assert!(completed(&results)
+        .iter()
+        .all(|&(generation, _)| generation != stale));
+    assert!(!worker.is_busy());
+}
+
+#[test]
+fn last_submitted_generation_wins() {
+    let worker = RenderWorker::spawn(|| {});
+    let renderer = Renderer::new(160, 120);
+    let jobs: Vec<MandelbrotParams> = (1..=5)
+        .map(|k| MandelbrotParams {
+            max_iter: 100 * k,
+            ..MandelbrotParams::default()
+        })
+        .collect();
+    let generations: Vec<u64> = jobs
+        .iter()
+        .map(|params| worker.submit(renderer, params.clone()))
+        .collect();
+    assert!(generations.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let last = *generations.last().unwrap();
+    let results = wait_for(&worker, last);
+    let completed = completed(&results);
+    let &(generation, frame) = completed.last().unwrap();
+    assert_eq!(generation, last);
+    assert_eq!(frame.pixels, renderer.render(&jobs[4]).pixels);
+    assert!(!worker.is_busy());
+
+    // Nothing older turns up afterwards.
+    thread::sleep(Duration::from_millis(50));
+    assert!(worker.drain().is_empty());
+
+    // A finished job's updates that were never drained are stale once a
+    // newer job is submitted.
+    worker.submit(renderer, jobs[0].clone());
+    while worker.is_busy() {
+        thread::sleep(Duration::from_millis(5));
+    }
+    let newest = worker.submit(renderer, jobs[1].clone());
+    let results = wait_for(&worker, newest);
+    assert!(results.iter().all(|result| result.generation == newest));
+}